@@ -6,6 +6,10 @@ use paste::paste;
 use super::IntoDuckDb;
 use crate::ffi;
 
+/// Values that can be appended to an appender
+///
+/// # Safety
+/// Implementations must append a value of the type the column expects.
 pub unsafe trait AppendParam {
     /// # Safety
     /// Does not need to check whether the type is correct
@@ -129,6 +133,18 @@ unsafe impl AppendParam for String {
     }
 }
 
+unsafe impl AppendParam for &String {
+    unsafe fn append_param_unchecked(self, appender: ffi::duckdb_appender) -> Result<(), String> {
+        self.as_str().append_param_unchecked(appender)
+    }
+}
+
+unsafe impl AppendParam for Vec<u8> {
+    unsafe fn append_param_unchecked(self, appender: ffi::duckdb_appender) -> Result<(), String> {
+        self.as_slice().append_param_unchecked(appender)
+    }
+}
+
 unsafe impl<Tz: TimeZone> AppendParam for DateTime<Tz> {
     unsafe fn append_param_unchecked(self, appender: ffi::duckdb_appender) -> Result<(), String> {
         match ffi::duckdb_append_timestamp(appender, self.into_duckdb()) {
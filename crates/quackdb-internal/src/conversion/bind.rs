@@ -1,4 +1,4 @@
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 
 use super::IntoDuckDb;
 use crate::ffi;
@@ -7,15 +7,41 @@ use bigdecimal::BigDecimal;
 use chrono::prelude::*;
 use paste::paste;
 
-/// Values that can bind to prepared statements
+/// Values that can bind to prepared statements.
+///
+/// Binding borrows the value, since DuckDB copies it into the statement.
+///
+/// # Safety
+/// Implementations must bind a valid value for the statement to copy.
 pub unsafe trait BindParam {
     /// # Safety
     /// Does not need to check whether the type is correct or whether index is in bounds.
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str>;
+    /// An owned copy binding the same value, kept to bind it again to another statement.
+    /// `None` if the value cannot be copied.
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        None
+    }
+}
+
+unsafe impl<T> BindParam for &T
+where
+    T: BindParam + ?Sized,
+{
+    unsafe fn bind_param_unchecked(
+        &self,
+        stmt: ffi::duckdb_prepared_statement,
+        param_idx: u64,
+    ) -> Result<(), &'static str> {
+        (**self).bind_param_unchecked(stmt, param_idx)
+    }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        (**self).to_owned_param()
+    }
 }
 
 /// `Option<T>` corresponds to nullable columns
@@ -24,7 +50,7 @@ where
     T: BindParam,
 {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
@@ -37,6 +63,12 @@ where
             },
         }
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        match self {
+            Some(t) => t.to_owned_param(),
+            None => Some(Box::new(None::<bool>)),
+        }
+    }
 }
 
 macro_rules! impl_bind_param_for_primitive {
@@ -53,16 +85,19 @@ macro_rules! impl_bind_param_for_primitive {
     ($ty:ty, $duck_ty:ty, $method:ident, $err_msg:expr) => {
         unsafe impl BindParam for $ty {
             unsafe fn bind_param_unchecked(
-                self,
+                &self,
                 stmt: ffi::duckdb_prepared_statement,
                 param_idx: u64,
             ) -> Result<(), &'static str> {
-                match ffi::$method(stmt, param_idx, self) {
+                match ffi::$method(stmt, param_idx, *self) {
                     ffi::DuckDBSuccess => Ok(()),
                     ffi::DuckDBError => Err($err_msg),
                     _ => unreachable!()
                 }
             }
+            fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+                Some(Box::new(*self))
+            }
         }
     };
 }
@@ -81,16 +116,19 @@ macro_rules! impl_bind_param {
     ($ty:ty, $duck_ty:ty, $method:ident, $err_msg:expr) => {
         unsafe impl BindParam for $ty {
             unsafe fn bind_param_unchecked(
-                self,
+                &self,
                 stmt: ffi::duckdb_prepared_statement,
                 param_idx: u64,
             ) -> Result<(), &'static str> {
-                match ffi::$method(stmt, param_idx, self.into_duckdb()) {
+                match ffi::$method(stmt, param_idx, (*self).into_duckdb()) {
                     ffi::DuckDBSuccess => Ok(()),
                     ffi::DuckDBError => Err($err_msg),
                     _ => unreachable!(),
                 }
             }
+            fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+                Some(Box::new(*self))
+            }
         }
     };
 }
@@ -113,12 +151,12 @@ impl_bind_param! {NaiveDateTime, timestamp}
 
 unsafe impl BindParam for BigDecimal {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
         let width = self.digits() as u8;
-        let (n, e) = self.into_bigint_and_exponent();
+        let (n, e) = self.as_bigint_and_exponent();
         let n: i128 = n.try_into().map_err(|_| "BigDecimal wider than i128")?;
         let decimal = ffi::duckdb_decimal {
             width,
@@ -131,11 +169,14 @@ unsafe impl BindParam for BigDecimal {
             _ => unreachable!(),
         }
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.clone()))
+    }
 }
 
-unsafe impl BindParam for &CStr {
+unsafe impl BindParam for CStr {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
@@ -145,11 +186,27 @@ unsafe impl BindParam for &CStr {
             _ => unreachable!(),
         }
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.to_owned()))
+    }
 }
 
-unsafe impl BindParam for &str {
+unsafe impl BindParam for CString {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
+        stmt: ffi::duckdb_prepared_statement,
+        param_idx: u64,
+    ) -> Result<(), &'static str> {
+        self.as_c_str().bind_param_unchecked(stmt, param_idx)
+    }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+unsafe impl BindParam for str {
+    unsafe fn bind_param_unchecked(
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
@@ -164,11 +221,14 @@ unsafe impl BindParam for &str {
             _ => unreachable!(),
         }
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.to_owned()))
+    }
 }
 
-unsafe impl BindParam for &[u8] {
+unsafe impl BindParam for [u8] {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
@@ -178,28 +238,53 @@ unsafe impl BindParam for &[u8] {
             _ => unreachable!(),
         }
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.to_vec()))
+    }
+}
+
+unsafe impl BindParam for Vec<u8> {
+    unsafe fn bind_param_unchecked(
+        &self,
+        stmt: ffi::duckdb_prepared_statement,
+        param_idx: u64,
+    ) -> Result<(), &'static str> {
+        self.as_slice().bind_param_unchecked(stmt, param_idx)
+    }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.clone()))
+    }
 }
 
 unsafe impl BindParam for String {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
         self.as_str().bind_param_unchecked(stmt, param_idx)
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.clone()))
+    }
 }
 
 unsafe impl<Tz: TimeZone> BindParam for DateTime<Tz> {
     unsafe fn bind_param_unchecked(
-        self,
+        &self,
         stmt: ffi::duckdb_prepared_statement,
         param_idx: u64,
     ) -> Result<(), &'static str> {
-        match ffi::duckdb_bind_timestamp(stmt, param_idx, self.into_duckdb()) {
+        let timestamp = ffi::duckdb_timestamp {
+            micros: self.timestamp_micros(),
+        };
+        match ffi::duckdb_bind_timestamp(stmt, param_idx, timestamp) {
             ffi::DuckDBSuccess => Ok(()),
             ffi::DuckDBError => Err("duckdb_bind_timestamp()"),
             _ => unreachable!(),
         }
     }
+    fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+        Some(Box::new(self.with_timezone(&Utc)))
+    }
 }
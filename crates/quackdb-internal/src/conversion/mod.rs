@@ -4,3 +4,7 @@ mod bind;
 pub use bind::*;
 mod append;
 pub use append::*;
+mod vector;
+pub use vector::*;
+mod value;
+pub use value::*;
@@ -59,13 +59,15 @@ unsafe impl ToDuckDbType for NaiveDateTime {
 unsafe impl IntoDuckDb for NaiveDateTime {
     fn into_duckdb(self) -> Self::DuckDbRepresentation {
         ffi::duckdb_timestamp {
-            micros: self.timestamp_micros(),
+            micros: self.and_utc().timestamp_micros(),
         }
     }
 }
 unsafe impl FromDuckDb for NaiveDateTime {
     fn from_duckdb(value: Self::DuckDbRepresentation) -> Self {
-        Self::from_timestamp_micros(value.micros).expect("from duckdb_timestamp")
+        DateTime::from_timestamp_micros(value.micros)
+            .expect("from duckdb_timestamp")
+            .naive_utc()
     }
 }
 
@@ -1,11 +1,12 @@
 mod primitive;
-pub use primitive::*;
 
 mod chrono;
 use crate::{handles::LogicalTypeHandle, type_id::TypeId};
-pub use chrono::*;
 
 /// Rust primitive types to duckdb types
+///
+/// # Safety
+/// `DuckDbRepresentation` must be how duckdb stores values of `DUCKDB_TYPE_ID`.
 pub unsafe trait ToDuckDbType {
     const DUCKDB_TYPE_ID: TypeId;
     /// Representation to interface with DuckDb
@@ -16,6 +17,8 @@ pub unsafe trait ToDuckDbType {
     }
 }
 
+/// # Safety
+/// The result must be a valid value of the duckdb type.
 pub unsafe trait IntoDuckDb
 where
     Self: ToDuckDbType,
@@ -25,6 +28,8 @@ where
     /// If unrepresentable
     fn into_duckdb(self) -> Self::DuckDbRepresentation;
 }
+/// # Safety
+/// Must accept any valid value of the duckdb type.
 pub unsafe trait FromDuckDb
 where
     Self: ToDuckDbType,
@@ -54,7 +54,7 @@ unsafe impl IntoDuckDb for i128 {
 }
 unsafe impl FromDuckDb for i128 {
     fn from_duckdb(value: Self::DuckDbRepresentation) -> Self {
-        (value.upper as i128) << 64 & value.lower as i128
+        ((value.upper as i128) << 64) | value.lower as i128
     }
 }
 
@@ -69,7 +69,24 @@ unsafe impl IntoDuckDb for &CStr {
     }
 }
 unsafe impl FromDuckDb for &CStr {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     fn from_duckdb(value: Self::DuckDbRepresentation) -> Self {
         unsafe { CStr::from_ptr(value) }
     }
 }
+
+/// Strings are stored as `duckdb_string_t` in vectors
+macro_rules! impl_to_duckdb_for_string {
+    ($ty:ty, $type_id:expr) => {
+        unsafe impl ToDuckDbType for $ty {
+            const DUCKDB_TYPE_ID: TypeId = $type_id;
+
+            type DuckDbRepresentation = ffi::duckdb_string_t;
+        }
+    };
+}
+
+impl_to_duckdb_for_string! { &str, TypeId::VarChar }
+impl_to_duckdb_for_string! { String, TypeId::VarChar }
+impl_to_duckdb_for_string! { &[u8], TypeId::Blob }
+impl_to_duckdb_for_string! { Vec<u8>, TypeId::Blob }
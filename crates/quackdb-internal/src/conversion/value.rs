@@ -0,0 +1,350 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::CString,
+    os::raw::c_char,
+};
+
+use super::BindParam;
+use crate::{
+    ffi,
+    handles::{LogicalTypeHandle, ValueHandle},
+    type_id::TypeId,
+};
+
+/// Values that can be built as a `duckdb_value`, used to bind `LIST`, `MAP` and `STRUCT`
+/// parameters.
+///
+/// The C API only creates `BIGINT` and `VARCHAR` scalars, so smaller integers widen to `BIGINT`.
+/// `u8` is left out so that `[u8]` and `Vec<u8>` keep binding as a `BLOB`.
+///
+/// # Safety
+/// Values must be of the type returned by `duckdb_logical_type`.
+pub unsafe trait ToDuckDbValue {
+    /// Type shared by all values of `Self`
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str>;
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str>;
+}
+
+unsafe impl<T> ToDuckDbValue for &T
+where
+    T: ToDuckDbValue + ?Sized,
+{
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        T::duckdb_logical_type()
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        (**self).to_duckdb_value()
+    }
+}
+
+macro_rules! impl_to_duckdb_value_for_int {
+    ($($ty:ty),+) => {
+        $(
+            unsafe impl ToDuckDbValue for $ty {
+                fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+                    Ok(unsafe { LogicalTypeHandle::from_id(TypeId::BigInt) })
+                }
+                fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+                    let raw = unsafe { ffi::duckdb_create_int64(*self as i64) };
+                    unsafe { value_from_raw(raw, "duckdb_create_int64()") }
+                }
+            }
+        )+
+    };
+}
+
+impl_to_duckdb_value_for_int! {i8, i16, i32, i64, u16, u32}
+
+unsafe impl ToDuckDbValue for str {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        Ok(unsafe { LogicalTypeHandle::from_id(TypeId::VarChar) })
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        unsafe {
+            let raw = ffi::duckdb_create_varchar_length(self.as_ptr().cast(), self.len() as u64);
+            value_from_raw(raw, "duckdb_create_varchar_length()")
+        }
+    }
+}
+
+unsafe impl ToDuckDbValue for String {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        str::duckdb_logical_type()
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        self.as_str().to_duckdb_value()
+    }
+}
+
+/// `[T]` corresponds to `LIST`
+unsafe impl<T: ToDuckDbValue> ToDuckDbValue for [T] {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        let child = T::duckdb_logical_type()?;
+        Ok(unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_create_list_type(*child)) })
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        let values = self
+            .iter()
+            .map(ToDuckDbValue::to_duckdb_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        list_value(T::duckdb_logical_type()?, &values)
+    }
+}
+
+unsafe impl<T: ToDuckDbValue> ToDuckDbValue for Vec<T> {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        <[T]>::duckdb_logical_type()
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        self.as_slice().to_duckdb_value()
+    }
+}
+
+unsafe impl<T: ToDuckDbValue, const N: usize> ToDuckDbValue for [T; N] {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        <[T]>::duckdb_logical_type()
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        self.as_slice().to_duckdb_value()
+    }
+}
+
+/// Maps are built as a `LIST` of `STRUCT(key, value)`, since the C API cannot create `MAP`
+/// values. Use `map_from_entries(?)` where a `MAP` is needed.
+unsafe impl<K: ToDuckDbValue, V: ToDuckDbValue, S> ToDuckDbValue for HashMap<K, V, S> {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        <[(K, V)]>::duckdb_logical_type()
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        entries_value(self.iter())
+    }
+}
+
+/// Same as `HashMap`, with entries in key order
+unsafe impl<K: ToDuckDbValue, V: ToDuckDbValue> ToDuckDbValue for BTreeMap<K, V> {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        <[(K, V)]>::duckdb_logical_type()
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        entries_value(self.iter())
+    }
+}
+
+/// Map entries are `(key, value)` structs
+unsafe impl<K: ToDuckDbValue, V: ToDuckDbValue> ToDuckDbValue for (K, V) {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        struct_type(&["key", "value"], <(K, V)>::field_types()?)
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        struct_value(Self::duckdb_logical_type()?, &self.field_values()?)
+    }
+}
+
+fn entries_value<'a, K, V>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+) -> Result<ValueHandle, &'static str>
+where
+    K: ToDuckDbValue + 'a,
+    V: ToDuckDbValue + 'a,
+{
+    let values = entries
+        .map(|entry| entry.to_duckdb_value())
+        .collect::<Result<Vec<_>, _>>()?;
+    list_value(<(K, V)>::duckdb_logical_type()?, &values)
+}
+
+/// Tuples of [`ToDuckDbValue`] fields of a `STRUCT`
+pub trait StructFields {
+    const LEN: usize;
+    fn field_types() -> Result<Vec<LogicalTypeHandle>, &'static str>;
+    fn field_values(&self) -> Result<Vec<ValueHandle>, &'static str>;
+}
+
+macro_rules! impl_struct_fields_for_tuple {
+    ($($idx:tt $ty:ident),+) => {
+        impl<$($ty: ToDuckDbValue),+> StructFields for ($($ty,)+) {
+            const LEN: usize = [$($idx),+].len();
+            fn field_types() -> Result<Vec<LogicalTypeHandle>, &'static str> {
+                Ok(vec![$($ty::duckdb_logical_type()?),+])
+            }
+            fn field_values(&self) -> Result<Vec<ValueHandle>, &'static str> {
+                Ok(vec![$(self.$idx.to_duckdb_value()?),+])
+            }
+        }
+    };
+}
+
+impl_struct_fields_for_tuple! {0 T0}
+impl_struct_fields_for_tuple! {0 T0, 1 T1}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14}
+impl_struct_fields_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14, 15 T15}
+
+/// Rust types bound as a `STRUCT`, mapped through a tuple of their fields
+///
+/// ```
+/// # use quackdb_internal::conversion::ToStruct;
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// impl ToStruct for Point {
+///     const FIELD_NAMES: &'static [&'static str] = &["x", "y"];
+///     type Fields = (i64, i64);
+///     fn to_fields(&self) -> (i64, i64) {
+///         (self.x, self.y)
+///     }
+/// }
+/// ```
+pub trait ToStruct {
+    /// One name per element of [`Fields`](Self::Fields)
+    const FIELD_NAMES: &'static [&'static str];
+    type Fields: StructFields;
+    fn to_fields(&self) -> Self::Fields;
+}
+
+/// A [`ToStruct`] value binding as a `STRUCT`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Struct<T>(pub T);
+
+unsafe impl<T: ToStruct> ToDuckDbValue for Struct<T> {
+    fn duckdb_logical_type() -> Result<LogicalTypeHandle, &'static str> {
+        if T::FIELD_NAMES.len() != T::Fields::LEN {
+            return Err("struct field names do not match its fields");
+        }
+        struct_type(T::FIELD_NAMES, T::Fields::field_types()?)
+    }
+    fn to_duckdb_value(&self) -> Result<ValueHandle, &'static str> {
+        let values = self.0.to_fields().field_values()?;
+        struct_value(Self::duckdb_logical_type()?, &values)
+    }
+}
+
+/// # Safety
+/// * Takes ownership of `raw`
+unsafe fn value_from_raw(
+    raw: ffi::duckdb_value,
+    function: &'static str,
+) -> Result<ValueHandle, &'static str> {
+    if raw.is_null() {
+        return Err(function);
+    }
+    Ok(ValueHandle::from_raw(raw))
+}
+
+fn list_value(
+    child: LogicalTypeHandle,
+    values: &[ValueHandle],
+) -> Result<ValueHandle, &'static str> {
+    // values are copied into the list
+    let mut raw: Vec<ffi::duckdb_value> = values.iter().map(|v| **v).collect();
+    unsafe {
+        let list = ffi::duckdb_create_list_value(*child, raw.as_mut_ptr(), raw.len() as u64);
+        value_from_raw(list, "duckdb_create_list_value()")
+    }
+}
+
+fn struct_type(
+    names: &[&str],
+    types: Vec<LogicalTypeHandle>,
+) -> Result<LogicalTypeHandle, &'static str> {
+    let names = names
+        .iter()
+        .map(|&name| CString::new(name))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "struct field name contains a nul byte")?;
+    let mut name_ptrs: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+    let mut raw_types: Vec<ffi::duckdb_logical_type> = types.iter().map(|t| **t).collect();
+    unsafe {
+        let raw = ffi::duckdb_create_struct_type(
+            raw_types.as_mut_ptr(),
+            name_ptrs.as_mut_ptr(),
+            names.len() as u64,
+        );
+        Ok(LogicalTypeHandle::from_raw(raw))
+    }
+}
+
+fn struct_value(
+    struct_type: LogicalTypeHandle,
+    values: &[ValueHandle],
+) -> Result<ValueHandle, &'static str> {
+    let mut raw: Vec<ffi::duckdb_value> = values.iter().map(|v| **v).collect();
+    unsafe {
+        let value = ffi::duckdb_create_struct_value(*struct_type, raw.as_mut_ptr());
+        value_from_raw(value, "duckdb_create_struct_value()")
+    }
+}
+
+/// # Safety
+/// See [`BindParam::bind_param_unchecked`]
+unsafe fn bind_value<T: ToDuckDbValue + ?Sized>(
+    value: &T,
+    stmt: ffi::duckdb_prepared_statement,
+    param_idx: u64,
+) -> Result<(), &'static str> {
+    let value = value.to_duckdb_value()?;
+    match ffi::duckdb_bind_value(stmt, param_idx, *value) {
+        ffi::DuckDBSuccess => Ok(()),
+        ffi::DuckDBError => Err("duckdb_bind_value()"),
+        _ => unreachable!(),
+    }
+}
+
+/// A value already built by [`ToDuckDbValue`], kept by [`BindParam::to_owned_param`]
+struct OwnedValue(ValueHandle);
+
+unsafe impl BindParam for OwnedValue {
+    unsafe fn bind_param_unchecked(
+        &self,
+        stmt: ffi::duckdb_prepared_statement,
+        param_idx: u64,
+    ) -> Result<(), &'static str> {
+        match ffi::duckdb_bind_value(stmt, param_idx, *self.0) {
+            ffi::DuckDBSuccess => Ok(()),
+            ffi::DuckDBError => Err("duckdb_bind_value()"),
+            _ => unreachable!(),
+        }
+    }
+}
+
+macro_rules! impl_bind_param_for_value {
+    ($([$($generics:tt)*] $ty:ty),+ $(,)?) => {
+        $(
+            unsafe impl<$($generics)*> BindParam for $ty {
+                unsafe fn bind_param_unchecked(
+                    &self,
+                    stmt: ffi::duckdb_prepared_statement,
+                    param_idx: u64,
+                ) -> Result<(), &'static str> {
+                    bind_value(self, stmt, param_idx)
+                }
+                fn to_owned_param(&self) -> Option<Box<dyn BindParam>> {
+                    let value = self.to_duckdb_value().ok()?;
+                    Some(Box::new(OwnedValue(value)))
+                }
+            }
+        )+
+    };
+}
+
+impl_bind_param_for_value! {
+    [T: ToDuckDbValue] [T],
+    [T: ToDuckDbValue] Vec<T>,
+    [T: ToDuckDbValue, const N: usize] [T; N],
+    [K: ToDuckDbValue, V: ToDuckDbValue, S] HashMap<K, V, S>,
+    [K: ToDuckDbValue, V: ToDuckDbValue] BTreeMap<K, V>,
+    [T: ToStruct] Struct<T>,
+}
@@ -0,0 +1,122 @@
+use chrono::prelude::*;
+
+use super::{IntoDuckDb, ToDuckDbType};
+use crate::{ffi, handles::LogicalTypeHandle, type_id::TypeId};
+
+/// Values that can be written into data chunk vectors
+///
+/// # Safety
+/// Values must be written in the layout of the vectors of `logical_type`.
+pub unsafe trait VectorParam {
+    /// Logical type of vectors holding this value
+    fn logical_type() -> LogicalTypeHandle;
+    /// # Safety
+    /// Does not need to check whether the vector type is correct or whether `row` is in bounds.
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64);
+}
+
+/// `Option<T>` corresponds to nullable columns
+unsafe impl<T> VectorParam for Option<T>
+where
+    T: VectorParam,
+{
+    fn logical_type() -> LogicalTypeHandle {
+        T::logical_type()
+    }
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+        match self {
+            Some(t) => t.write_vector_unchecked(vector, row),
+            None => {
+                ffi::duckdb_vector_ensure_validity_writable(vector);
+                ffi::duckdb_validity_set_row_invalid(ffi::duckdb_vector_get_validity(vector), row);
+            }
+        }
+    }
+}
+
+macro_rules! impl_vector_param {
+    ($ty:ty) => {
+        unsafe impl VectorParam for $ty {
+            fn logical_type() -> LogicalTypeHandle {
+                <$ty as ToDuckDbType>::logical_type()
+            }
+            unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+                let data: *mut <$ty as ToDuckDbType>::DuckDbRepresentation =
+                    ffi::duckdb_vector_get_data(vector).cast();
+                data.add(row as usize).write(self.into_duckdb());
+            }
+        }
+    };
+}
+
+impl_vector_param! {bool}
+impl_vector_param! {i8}
+impl_vector_param! {i16}
+impl_vector_param! {i32}
+impl_vector_param! {i64}
+impl_vector_param! {i128}
+impl_vector_param! {u8}
+impl_vector_param! {u16}
+impl_vector_param! {u32}
+impl_vector_param! {u64}
+impl_vector_param! {f32}
+impl_vector_param! {f64}
+impl_vector_param! {NaiveDate}
+impl_vector_param! {NaiveTime}
+impl_vector_param! {NaiveDateTime}
+
+unsafe impl<Tz: TimeZone> VectorParam for DateTime<Tz> {
+    fn logical_type() -> LogicalTypeHandle {
+        <Self as ToDuckDbType>::logical_type()
+    }
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+        let data: *mut ffi::duckdb_timestamp = ffi::duckdb_vector_get_data(vector).cast();
+        data.add(row as usize).write(self.into_duckdb());
+    }
+}
+
+unsafe impl VectorParam for &str {
+    fn logical_type() -> LogicalTypeHandle {
+        unsafe { LogicalTypeHandle::from_id(TypeId::VarChar) }
+    }
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+        ffi::duckdb_vector_assign_string_element_len(
+            vector,
+            row,
+            self.as_ptr().cast(),
+            self.len() as u64,
+        )
+    }
+}
+
+unsafe impl VectorParam for String {
+    fn logical_type() -> LogicalTypeHandle {
+        <&str as VectorParam>::logical_type()
+    }
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+        self.as_str().write_vector_unchecked(vector, row)
+    }
+}
+
+unsafe impl VectorParam for &[u8] {
+    fn logical_type() -> LogicalTypeHandle {
+        unsafe { LogicalTypeHandle::from_id(TypeId::Blob) }
+    }
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+        ffi::duckdb_vector_assign_string_element_len(
+            vector,
+            row,
+            self.as_ptr().cast(),
+            self.len() as u64,
+        )
+    }
+}
+
+unsafe impl VectorParam for Vec<u8> {
+    fn logical_type() -> LogicalTypeHandle {
+        <&[u8] as VectorParam>::logical_type()
+    }
+    unsafe fn write_vector_unchecked(self, vector: ffi::duckdb_vector, row: u64) {
+        self.as_slice().write_vector_unchecked(vector, row)
+    }
+}
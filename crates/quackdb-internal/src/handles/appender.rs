@@ -1,12 +1,14 @@
-use std::{ops::Deref, sync::Arc};
+use std::{ffi::CStr, ops::Deref, sync::Arc};
 
 use crate::ffi;
 
 use super::ConnectionHandle;
 
+#[derive(Debug)]
 pub struct AppenderHandle {
     raw: ffi::duckdb_appender,
-    _parent: Arc<ConnectionHandle>,
+    closed: bool,
+    parent: Arc<ConnectionHandle>,
 }
 
 impl AppenderHandle {
@@ -15,9 +17,47 @@ impl AppenderHandle {
     pub unsafe fn from_raw(raw: ffi::duckdb_appender, connection: Arc<ConnectionHandle>) -> Self {
         Self {
             raw,
-            _parent: connection,
+            closed: false,
+            parent: connection,
         }
     }
+    /// Connection the appender writes through
+    pub fn connection(&self) -> &Arc<ConnectionHandle> {
+        &self.parent
+    }
+    /// Flush and close the appender, returning the error of the final flush
+    pub fn close(&mut self) -> Result<(), String> {
+        self.closed = true;
+        unsafe {
+            if ffi::duckdb_appender_close(self.raw) != ffi::DuckDBSuccess {
+                let err = CStr::from_ptr(ffi::duckdb_appender_error(self.raw));
+                return Err(err.to_string_lossy().into_owned());
+            }
+        }
+        Ok(())
+    }
+    /// Close in the span `quackdb` opens around explicit flushes, marked as implicit
+    #[cfg(feature = "tracing")]
+    fn close_traced(&mut self) -> Result<(), String> {
+        let span = tracing::debug_span!(
+            target: "quackdb",
+            "quackdb.appender_flush",
+            implicit = true,
+            duration_us = tracing::field::Empty,
+            error = tracing::field::Empty,
+        );
+        let start = std::time::Instant::now();
+        let result = span.in_scope(|| self.close());
+        span.record("duration_us", start.elapsed().as_micros() as u64);
+        if let Err(err) = &result {
+            span.record("error", err.as_str());
+        }
+        result
+    }
+    #[cfg(not(feature = "tracing"))]
+    fn close_traced(&mut self) -> Result<(), String> {
+        self.close()
+    }
 }
 
 impl Deref for AppenderHandle {
@@ -30,10 +70,14 @@ impl Deref for AppenderHandle {
 
 impl Drop for AppenderHandle {
     fn drop(&mut self) {
-        unsafe {
-            if ffi::duckdb_appender_destroy(&mut self.raw) != ffi::DuckDBSuccess {
-                panic!("duckdb_appender_destroy() failed");
+        if !self.closed {
+            if let Err(err) = self.close_traced() {
+                log::error!("failed to close appender, rows may be lost: {err}");
             }
         }
+        // A failed close was reported already, and destroying retries the same flush
+        unsafe {
+            ffi::duckdb_appender_destroy(&mut self.raw);
+        }
     }
 }
@@ -1,4 +1,10 @@
-use std::{ops::Deref, sync::Arc};
+use std::{
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use crate::ffi;
 
@@ -7,9 +13,18 @@ use super::DatabaseHandle;
 #[derive(Debug)]
 pub struct ConnectionHandle {
     raw: ffi::duckdb_connection,
-    _parent: Arc<DatabaseHandle>,
+    parent: Arc<DatabaseHandle>,
+    /// Statements prepared or run so far, see [`ConnectionHandle::begin_query`]
+    queries: AtomicU64,
 }
 
+// SAFETY: duckdb serializes calls on a connection with its client context lock, so the
+// handle may be used and dropped from any thread. A query closes the streaming result
+// open on the connection, even from the same thread, but that only makes further fetches
+// return nothing, which streaming results detect with `ConnectionHandle::query_count`.
+unsafe impl Send for ConnectionHandle {}
+unsafe impl Sync for ConnectionHandle {}
+
 pub struct ConnectionHandleError;
 
 impl ConnectionHandle {
@@ -18,9 +33,23 @@ impl ConnectionHandle {
     pub unsafe fn from_raw(raw: ffi::duckdb_connection, parent: Arc<DatabaseHandle>) -> Arc<Self> {
         Arc::new(Self {
             raw,
-            _parent: parent,
+            parent,
+            queries: AtomicU64::new(0),
         })
     }
+    /// Database the connection belongs to
+    pub fn database(&self) -> &Arc<DatabaseHandle> {
+        &self.parent
+    }
+    /// Count a statement about to be prepared or run, which closes any result still open
+    /// on the connection. Returns the count including this statement.
+    pub fn begin_query(&self) -> u64 {
+        self.queries.fetch_add(1, Ordering::Relaxed) + 1
+    }
+    /// Statements prepared or run so far
+    pub fn query_count(&self) -> u64 {
+        self.queries.load(Ordering::Relaxed)
+    }
 }
 
 impl Deref for ConnectionHandle {
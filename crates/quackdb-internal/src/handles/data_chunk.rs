@@ -0,0 +1,31 @@
+use std::ops::Deref;
+
+use crate::ffi;
+
+/// An owned data chunk, e.g. fetched from a result
+#[derive(Debug)]
+pub struct DataChunkHandle {
+    raw: ffi::duckdb_data_chunk,
+}
+
+impl DataChunkHandle {
+    /// # Safety
+    /// * Takes ownership of `raw`
+    pub unsafe fn from_raw(raw: ffi::duckdb_data_chunk) -> Self {
+        Self { raw }
+    }
+}
+
+impl Deref for DataChunkHandle {
+    type Target = ffi::duckdb_data_chunk;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl Drop for DataChunkHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_data_chunk(&mut self.raw) }
+    }
+}
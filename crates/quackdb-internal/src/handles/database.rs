@@ -7,6 +7,11 @@ pub struct DatabaseHandle {
     raw: ffi::duckdb_database,
 }
 
+// SAFETY: a duckdb database instance synchronizes access from multiple connections
+// internally, so it can be shared and dropped from any thread
+unsafe impl Send for DatabaseHandle {}
+unsafe impl Sync for DatabaseHandle {}
+
 impl DatabaseHandle {
     /// # Safety
     /// * Takes ownership of `raw`
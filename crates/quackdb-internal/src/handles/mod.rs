@@ -6,9 +6,17 @@ mod config;
 pub use config::*;
 mod connection;
 pub use connection::*;
+mod data_chunk;
+pub use data_chunk::*;
 mod database;
 pub use database::*;
+mod pending;
+pub use pending::*;
+mod result;
+pub use result::*;
 mod statement;
 pub use statement::*;
 mod logical_type;
 pub use logical_type::*;
+mod value;
+pub use value::*;
@@ -0,0 +1,37 @@
+use std::{ffi::CStr, ops::Deref};
+
+use crate::ffi;
+
+#[derive(Debug)]
+pub struct PendingResultHandle {
+    raw: ffi::duckdb_pending_result,
+}
+
+impl PendingResultHandle {
+    /// # Safety
+    /// * Takes ownership of `raw`
+    pub unsafe fn from_raw(raw: ffi::duckdb_pending_result) -> Self {
+        Self { raw }
+    }
+    /// Error message of a failed pending query, if any
+    pub fn error(&self) -> Option<String> {
+        unsafe {
+            let err = ffi::duckdb_pending_error(self.raw);
+            (!err.is_null()).then(|| CStr::from_ptr(err).to_string_lossy().into_owned())
+        }
+    }
+}
+
+impl Deref for PendingResultHandle {
+    type Target = ffi::duckdb_pending_result;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl Drop for PendingResultHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_pending(&mut self.raw) }
+    }
+}
@@ -0,0 +1,74 @@
+use std::{ffi::CStr, ops::Deref, sync::Arc};
+
+use crate::ffi;
+
+use super::{ConnectionHandle, PreparedStatementHandle};
+
+#[derive(Debug)]
+pub struct QueryResultHandle {
+    raw: ffi::duckdb_result,
+    parent: QueryResultParent,
+}
+
+#[derive(Debug)]
+pub enum QueryResultParent {
+    Connection(Arc<ConnectionHandle>),
+    Statement(Arc<PreparedStatementHandle>),
+}
+
+impl QueryResultHandle {
+    /// # Safety
+    /// * Takes ownership of `raw`
+    pub unsafe fn from_raw_connection(
+        raw: ffi::duckdb_result,
+        connection: Arc<ConnectionHandle>,
+    ) -> Self {
+        Self {
+            raw,
+            parent: QueryResultParent::Connection(connection),
+        }
+    }
+    /// # Safety
+    /// * Takes ownership of `raw`
+    pub unsafe fn from_raw_statement(
+        raw: ffi::duckdb_result,
+        statement: Arc<PreparedStatementHandle>,
+    ) -> Self {
+        Self {
+            raw,
+            parent: QueryResultParent::Statement(statement),
+        }
+    }
+    /// Connection the result was produced on
+    pub fn connection(&self) -> &Arc<ConnectionHandle> {
+        match &self.parent {
+            QueryResultParent::Connection(connection) => connection,
+            QueryResultParent::Statement(statement) => statement.connection(),
+        }
+    }
+    /// Pointer for functions taking `duckdb_result *`
+    pub fn as_ptr(&self) -> *mut ffi::duckdb_result {
+        std::ptr::addr_of!(self.raw).cast_mut()
+    }
+    /// Error message of a failed query, if any
+    pub fn error(&self) -> Option<String> {
+        unsafe {
+            let err = ffi::duckdb_result_error(self.as_ptr());
+            (!err.is_null()).then(|| CStr::from_ptr(err).to_string_lossy().into_owned())
+        }
+    }
+}
+
+impl Deref for QueryResultHandle {
+    type Target = ffi::duckdb_result;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl Drop for QueryResultHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_result(&mut self.raw) }
+    }
+}
@@ -7,7 +7,8 @@ use super::ConnectionHandle;
 #[derive(Debug)]
 pub struct PreparedStatementHandle {
     raw: ffi::duckdb_prepared_statement,
-    _parent: Arc<ConnectionHandle>,
+    parent: Arc<ConnectionHandle>,
+    query: String,
 }
 
 impl Deref for PreparedStatementHandle {
@@ -29,13 +30,24 @@ impl Drop for PreparedStatementHandle {
 impl PreparedStatementHandle {
     /// # Safety
     /// * Takes ownership of `raw`
+    #[allow(clippy::arc_with_non_send_sync)]
     pub unsafe fn from_raw(
         raw: ffi::duckdb_prepared_statement,
         parent: Arc<ConnectionHandle>,
+        query: &str,
     ) -> Arc<Self> {
         Arc::new(Self {
             raw,
-            _parent: parent,
+            parent,
+            query: query.to_owned(),
         })
     }
+    /// Connection the statement was prepared on
+    pub fn connection(&self) -> &Arc<ConnectionHandle> {
+        &self.parent
+    }
+    /// SQL text the statement was prepared from
+    pub fn query(&self) -> &str {
+        &self.query
+    }
 }
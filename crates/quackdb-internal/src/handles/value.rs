@@ -0,0 +1,34 @@
+use std::ops::Deref;
+
+use crate::ffi;
+
+#[derive(Debug)]
+pub struct ValueHandle {
+    raw: ffi::duckdb_value,
+}
+
+// SAFETY: duckdb values are immutable after creation and not tied to a thread
+unsafe impl Send for ValueHandle {}
+unsafe impl Sync for ValueHandle {}
+
+impl ValueHandle {
+    /// # Safety
+    /// * Takes ownership of `raw`
+    pub unsafe fn from_raw(raw: ffi::duckdb_value) -> Self {
+        Self { raw }
+    }
+}
+
+impl Deref for ValueHandle {
+    type Target = ffi::duckdb_value;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl Drop for ValueHandle {
+    fn drop(&mut self) {
+        unsafe { ffi::duckdb_destroy_value(&mut self.raw) }
+    }
+}
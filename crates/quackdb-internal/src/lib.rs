@@ -10,6 +10,6 @@ pub mod type_id;
 pub fn library_version() -> String {
     unsafe {
         let p = CStr::from_ptr(ffi::duckdb_library_version());
-        p.to_string_lossy().to_owned().to_string()
+        p.to_string_lossy().into_owned()
     }
 }
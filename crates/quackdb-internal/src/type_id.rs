@@ -2,7 +2,7 @@ use strum::FromRepr;
 
 use crate::ffi;
 
-#[derive(Debug, Clone, Copy, FromRepr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr)]
 #[repr(u32)]
 #[non_exhaustive]
 pub enum TypeId {
@@ -0,0 +1,482 @@
+mod row;
+pub use row::*;
+
+use std::{cell::OnceCell, ffi::CStr, ops::Deref};
+
+use quackdb_internal::{
+    conversion::{AppendParam, ToDuckDbType},
+    ffi,
+    handles::{AppenderHandle, LogicalTypeHandle},
+    type_id::TypeId,
+};
+use thiserror::Error;
+
+use crate::{connection::Connection, trace::span, types::LogicalType};
+
+#[derive(Debug)]
+pub struct Appender {
+    pub handle: AppenderHandle,
+    /// Schema and name of the target table, when created by name
+    target: Option<(Option<String>, String)>,
+    /// Names and nullability of the target columns in table order, read on first use
+    columns: OnceCell<Vec<ColumnInfo>>,
+    /// Values appended to the current row so far
+    row_values: usize,
+    /// Set when a row failed after some of its values were appended
+    poisoned: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnInfo {
+    pub name: String,
+    pub nullable: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum AppenderError {
+    #[error("appender flush: {0}")]
+    FlushError(String),
+    #[error("appender error: {0}")]
+    AppendError(String),
+    #[error("unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("column {column} is {expected:?}, not {actual:?}")]
+    TypeMismatch {
+        column: String,
+        expected: Option<TypeId>,
+        actual: TypeId,
+    },
+    #[error("column {0} is not nullable")]
+    NotNullable(String),
+    #[error("cannot read the columns of {table}: {reason}")]
+    ColumnsError { table: String, reason: String },
+    #[error("appender was left mid-row by a failed append and must be dropped")]
+    Poisoned,
+    #[error("row {row}: {source}")]
+    RowError {
+        row: usize,
+        source: Box<AppenderError>,
+    },
+}
+
+impl Appender {
+    pub(crate) fn for_table(handle: AppenderHandle, schema: Option<String>, table: String) -> Self {
+        Self {
+            target: Some((schema, table)),
+            ..handle.into()
+        }
+    }
+    /// # Safety
+    /// There must actually be an error
+    pub unsafe fn error(&self) -> String {
+        let err = unsafe { CStr::from_ptr(ffi::duckdb_appender_error(**self)) };
+        err.to_string_lossy().into_owned()
+    }
+    pub fn flush(&self) -> Result<(), AppenderError> {
+        if self.poisoned {
+            return Err(AppenderError::Poisoned);
+        }
+        let span = span!("appender_flush");
+        let result = span.in_scope(|| match unsafe { ffi::duckdb_appender_flush(**self) } {
+            ffi::DuckDBSuccess => Ok(()),
+            ffi::DuckDBError => Err(AppenderError::FlushError(unsafe { self.error() })),
+            _ => unreachable!(),
+        });
+        span.finish(result)
+    }
+    /// Flush remaining rows and close the appender.
+    ///
+    /// Dropping the appender also closes it, but can only log errors.
+    pub fn close(mut self) -> Result<(), AppenderError> {
+        let span = span!("appender_flush");
+        let result = span.in_scope(|| self.handle.close().map_err(AppenderError::FlushError));
+        span.finish(result)
+    }
+    pub fn column_count(&self) -> u64 {
+        unsafe { ffi::duckdb_appender_column_count(**self) }
+    }
+    /// Type of a column, or `None` if out of range
+    pub fn column_type(&self, column: u64) -> Option<LogicalType> {
+        if column >= self.column_count() {
+            return None;
+        }
+        let raw = unsafe { ffi::duckdb_appender_column_type(**self, column) };
+        Some(unsafe { LogicalTypeHandle::from_raw(raw) }.into())
+    }
+    /// Names and types of the target columns.
+    ///
+    /// Names are only known for appenders created by [`Connection::appender`], otherwise
+    /// they are empty. They are read with a query on the connection the first time they
+    /// are needed, which closes any result streaming from it.
+    pub fn columns(&self) -> Result<Vec<(String, LogicalType)>, AppenderError> {
+        let infos = self.column_infos()?;
+        Ok((0..self.column_count())
+            .filter_map(|i| {
+                let name = infos
+                    .get(i as usize)
+                    .map_or_else(String::new, |c| c.name.clone());
+                Some((name, self.column_type(i)?))
+            })
+            .collect())
+    }
+    fn column_infos(&self) -> Result<&[ColumnInfo], AppenderError> {
+        if let Some(columns) = self.columns.get() {
+            return Ok(columns);
+        }
+        let columns = match &self.target {
+            Some((schema, table)) => {
+                let connection = Connection::from(self.handle.connection().clone());
+                let columns = connection
+                    .appender_columns(schema.as_deref(), table)
+                    .map_err(|e| AppenderError::ColumnsError {
+                        table: table.clone(),
+                        reason: e.to_string(),
+                    })?;
+                if columns.is_empty() {
+                    return Err(AppenderError::ColumnsError {
+                        table: table.clone(),
+                        reason: "table not found".to_owned(),
+                    });
+                }
+                columns
+            }
+            None => Vec::new(),
+        };
+        Ok(self.columns.get_or_init(|| columns))
+    }
+    pub fn append<T: AppendParam>(&mut self, value: T) -> Result<&mut Self, AppenderError> {
+        if self.poisoned {
+            return Err(AppenderError::Poisoned);
+        }
+        unsafe {
+            value
+                .append_param_unchecked(**self)
+                .map_err(|_| AppenderError::AppendError(self.error()))?;
+        }
+        self.row_values += 1;
+        Ok(self)
+    }
+    pub fn end_row(&mut self) -> Result<&mut Self, AppenderError> {
+        if self.poisoned {
+            return Err(AppenderError::Poisoned);
+        }
+        match unsafe { ffi::duckdb_appender_end_row(**self) } {
+            ffi::DuckDBSuccess => {
+                self.row_values = 0;
+                Ok(self)
+            }
+            ffi::DuckDBError => Err(AppenderError::AppendError(unsafe { self.error() })),
+            _ => unreachable!(),
+        }
+    }
+    /// Append a full row and end it.
+    ///
+    /// If a value after the first one fails, the row cannot be completed: the appender
+    /// then rejects further rows and flushes with [`AppenderError::Poisoned`] and must be
+    /// dropped. Rows not flushed before the failure are lost.
+    pub fn append_row<R: AppendRow>(&mut self, row: R) -> Result<&mut Self, AppenderError> {
+        if self.poisoned {
+            return Err(AppenderError::Poisoned);
+        }
+        if self.row_values > 0 {
+            return Err(AppenderError::AppendError(
+                "previous row was not ended".to_owned(),
+            ));
+        }
+        let result = row.append_to(self).and_then(|_| self.end_row().map(|_| ()));
+        self.poison_on_error(result)?;
+        Ok(self)
+    }
+    fn poison_on_error(&mut self, result: Result<(), AppenderError>) -> Result<(), AppenderError> {
+        if result.is_err() && self.row_values > 0 {
+            self.poisoned = true;
+        }
+        result
+    }
+    /// Append and end each row, reporting the index of a failing row.
+    ///
+    /// As with [`append_row`](Self::append_row), the appender must be dropped if the
+    /// failing row was left incomplete.
+    pub fn append_rows<R: AppendRow>(
+        &mut self,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<&mut Self, AppenderError> {
+        for (row, values) in rows.into_iter().enumerate() {
+            self.append_row(values)
+                .map_err(|e| AppenderError::RowError {
+                    row,
+                    source: Box::new(e),
+                })?;
+        }
+        Ok(self)
+    }
+    /// Start a row whose values are set by column name.
+    ///
+    /// The first call reads the column names, as [`columns`](Self::columns) does.
+    pub fn row(&mut self) -> Result<RowBuilder<'_>, AppenderError> {
+        self.column_infos()?;
+        let count = self.column_count() as usize;
+        let types = (0..count as u64)
+            .map(|i| self.column_type(i).and_then(|t| t.type_id()))
+            .collect();
+        Ok(RowBuilder {
+            appender: self,
+            types,
+            values: (0..count).map(|_| None).collect(),
+        })
+    }
+}
+
+type AppendFn<'a> = Box<dyn FnOnce(&mut Appender) -> Result<(), AppenderError> + 'a>;
+
+/// A row of an [`Appender`] with values set by column name in any order.
+///
+/// Types are checked when values are set. Columns left unset are filled with `NULL` by
+/// [`finish`](Self::finish), which fails if one of them is not nullable.
+pub struct RowBuilder<'a> {
+    appender: &'a mut Appender,
+    types: Vec<Option<TypeId>>,
+    values: Vec<Option<AppendFn<'a>>>,
+}
+
+impl<'a> RowBuilder<'a> {
+    /// Set a column, replacing any value set before
+    pub fn set<T>(&mut self, column: &str, value: T) -> Result<&mut Self, AppenderError>
+    where
+        T: AppendParam + ToDuckDbType + 'a,
+    {
+        let index = self.index(column)?;
+        if self.types[index] != Some(T::DUCKDB_TYPE_ID) {
+            return Err(AppenderError::TypeMismatch {
+                column: column.to_owned(),
+                expected: self.types[index],
+                actual: T::DUCKDB_TYPE_ID,
+            });
+        }
+        self.values[index] = Some(Box::new(move |appender: &mut Appender| {
+            appender.append(value).map(|_| ())
+        }));
+        Ok(self)
+    }
+    /// Set a column to a value or `NULL`
+    pub fn set_opt<T>(&mut self, column: &str, value: Option<T>) -> Result<&mut Self, AppenderError>
+    where
+        T: AppendParam + ToDuckDbType + 'a,
+    {
+        match value {
+            Some(value) => self.set(column, value),
+            None => self.set_null(column),
+        }
+    }
+    pub fn set_null(&mut self, column: &str) -> Result<&mut Self, AppenderError> {
+        let index = self.index(column)?;
+        if !self.is_nullable(index) {
+            return Err(AppenderError::NotNullable(column.to_owned()));
+        }
+        self.values[index] = Some(Box::new(|appender: &mut Appender| {
+            appender.append(None::<i32>).map(|_| ())
+        }));
+        Ok(self)
+    }
+    /// Append all columns in table order and end the row. The builder is then empty.
+    ///
+    /// If appending fails partway through the row, the appender must be dropped, as with
+    /// [`Appender::append_row`].
+    pub fn finish(&mut self) -> Result<(), AppenderError> {
+        for (index, value) in self.values.iter().enumerate() {
+            if value.is_none() && !self.is_nullable(index) {
+                return Err(AppenderError::NotNullable(
+                    self.columns()[index].name.clone(),
+                ));
+            }
+        }
+        let values: Vec<_> = self.values.iter_mut().map(Option::take).collect();
+        let append_all = |appender: &mut Appender| {
+            for value in values {
+                match value {
+                    Some(append) => append(appender)?,
+                    None => {
+                        appender.append(None::<i32>)?;
+                    }
+                }
+            }
+            appender.end_row().map(|_| ())
+        };
+        let result = append_all(self.appender);
+        self.appender.poison_on_error(result)
+    }
+
+    /// Columns of the appender, read by [`Appender::row`] before the builder was made
+    fn columns(&self) -> &[ColumnInfo] {
+        self.appender.columns.get().map_or(&[], Vec::as_slice)
+    }
+    fn index(&self, column: &str) -> Result<usize, AppenderError> {
+        self.columns()
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(column))
+            .filter(|&i| i < self.values.len())
+            .ok_or_else(|| AppenderError::UnknownColumn(column.to_owned()))
+    }
+    fn is_nullable(&self, index: usize) -> bool {
+        self.columns().get(index).is_none_or(|c| c.nullable)
+    }
+}
+
+impl From<AppenderHandle> for Appender {
+    fn from(value: AppenderHandle) -> Self {
+        Self {
+            handle: value,
+            target: None,
+            columns: OnceCell::new(),
+            row_values: 0,
+            poisoned: false,
+        }
+    }
+}
+
+impl Deref for Appender {
+    type Target = ffi::duckdb_appender;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_row_builder() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER NOT NULL, name VARCHAR, score DOUBLE)")?;
+        let mut appender = conn.appender(None, "T")?;
+        let columns = appender.columns()?;
+        let names: Vec<_> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "score"]);
+        assert_eq!(columns[2].1.type_id(), Some(TypeId::Double));
+
+        appender.row()?.set("score", 1.5)?.set("ID", 1)?.finish()?;
+        {
+            let mut row = appender.row()?;
+            row.set("name", "two")?.set("id", 2)?;
+            row.finish()?;
+        }
+        appender
+            .row()?
+            .set("id", 3)?
+            .set_opt("name", None::<&str>)?
+            .finish()?;
+        assert!(matches!(
+            appender.row()?.set("id", 4i64),
+            Err(AppenderError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            appender.row()?.set("missing", 4),
+            Err(AppenderError::UnknownColumn(_))
+        ));
+        assert!(matches!(
+            appender.row()?.set_null("id"),
+            Err(AppenderError::NotNullable(_))
+        ));
+        assert!(matches!(
+            appender.row()?.set("name", "no id")?.finish(),
+            Err(AppenderError::NotNullable(_))
+        ));
+        drop(appender);
+
+        let result =
+            conn.query_result("SELECT count(*), count(name), sum(score), sum(id) FROM t")?;
+        assert_eq!(result.get::<i64>(0, 0)?, Some(3));
+        assert_eq!(result.get::<i64>(1, 0)?, Some(1));
+        assert_eq!(result.get::<f64>(2, 0)?, Some(1.5));
+        assert_eq!(result.get::<i128>(3, 0)?, Some(6));
+        Ok(())
+    }
+
+    #[test]
+    fn test_temp_table_columns() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(other INTEGER)")?;
+        conn.query("CREATE TEMP TABLE t(id INTEGER, name VARCHAR)")?;
+        let mut appender = conn.appender(None, "t")?;
+        let names: Vec<_> = appender.columns()?.into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["id", "name"]);
+        appender.row()?.set("name", "a")?.set("id", 1)?.finish()?;
+        appender.close()?;
+        let count = conn.query_result("SELECT count(*) FROM temp.main.t")?;
+        assert_eq!(count.get::<i64>(0, 0)?, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_columns_read_lazily() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER, name VARCHAR)")?;
+        conn.set_statement_cache_capacity(1);
+        let cached = std::sync::Arc::downgrade(&conn.prepare_cached("SELECT 1")?.handle);
+
+        let mut appender = conn.appender(None, "t")?;
+        assert_eq!(appender.column_count(), 2);
+        appender.append_row((1, "one"))?;
+        appender.row()?.set("id", 2)?.finish()?;
+        assert!(cached.upgrade().is_some());
+        appender.close()?;
+        let count = conn.query_result("SELECT count(*) FROM t")?;
+        assert_eq!(count.get::<i64>(0, 0)?, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_rows() -> Result<(), QuackError> {
+        struct Record {
+            id: &'static str,
+            name: String,
+            score: f64,
+        }
+
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER, name VARCHAR, score DOUBLE)")?;
+        let mut appender = conn.appender(None, "t")?;
+        appender
+            .append_row((1, "one", 1.0))?
+            .append_rows((2..10).map(|i| (i, format!("n{i}"), i as f64)))?;
+        let records = [
+            Record {
+                id: "10",
+                name: "ten".to_owned(),
+                score: 10.0,
+            },
+            Record {
+                id: "eleven",
+                name: "eleven".to_owned(),
+                score: 11.0,
+            },
+        ];
+        // "eleven" fails as the first value of its row, so the appender stays usable
+        assert!(matches!(
+            appender.append_rows(records.iter().map(|r| (r.id, &r.name, r.score))),
+            Err(AppenderError::RowError { row: 1, .. })
+        ));
+        // A failure in the second column leaves the row incomplete, losing unflushed rows
+        appender.flush()?;
+        assert!(appender.append_row((11, "eleven", "not a score")).is_err());
+        assert!(matches!(
+            appender.append_row((12, "twelve", 12.0)),
+            Err(AppenderError::Poisoned)
+        ));
+        assert!(matches!(appender.end_row(), Err(AppenderError::Poisoned)));
+        drop(appender);
+
+        let result = conn.query_result("SELECT count(*), sum(score) FROM t")?;
+        assert_eq!(result.get::<i64>(0, 0)?, Some(10));
+        assert_eq!(result.get::<f64>(1, 0)?, Some(55.0));
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use quackdb_internal::conversion::AppendParam;
+
+use super::{Appender, AppenderError};
+
+/// A full row of an [`Appender`], implemented for tuples of up to 16 [`AppendParam`] values
+pub trait AppendRow {
+    fn append_to(self, appender: &mut Appender) -> Result<(), AppenderError>;
+}
+
+macro_rules! impl_append_row_for_tuple {
+    ($($idx:tt $ty:ident),+) => {
+        impl<$($ty: AppendParam),+> AppendRow for ($($ty,)+) {
+            fn append_to(self, appender: &mut Appender) -> Result<(), AppenderError> {
+                appender$(.append(self.$idx)?)+;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_append_row_for_tuple! {0 T0}
+impl_append_row_for_tuple! {0 T0, 1 T1}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14}
+impl_append_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14, 15 T15}
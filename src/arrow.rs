@@ -1,36 +1,57 @@
 use std::{
-    ffi::{c_char, CStr},
+    ffi::{c_char, CStr, CString},
     ops::Deref,
-    ptr,
+    sync::{Arc, OnceLock},
 };
 
 use cstr::cstr;
 
 use arrow::{
+    array::{Array, AsArray, BooleanArray, ListArray, StringArray},
+    datatypes::{Schema, SchemaRef},
     error::ArrowError,
     ffi::{FFI_ArrowArray, FFI_ArrowSchema},
     ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream},
+    record_batch::RecordBatch,
 };
 use thiserror::Error;
 
-use quackdb_internal::{ffi, handles::ArrowResultHandle};
+use quackdb_internal::{
+    ffi,
+    handles::{ArrowResultHandle, DataChunkHandle, LogicalTypeHandle, QueryResultHandle},
+};
+
+use crate::{
+    panic::{catch_panic, error_cstring},
+    trace::{span, Span},
+    types::{to_arrow_schema, LogicalType, LogicalTypeError},
+};
 
 #[derive(Debug)]
 pub struct ArrowResult {
     pub handle: ArrowResultHandle,
+    /// Schema fetched on first use, shared with the stream
+    schema: OnceLock<SchemaRef>,
 }
 
 #[derive(Error, Debug)]
 pub enum ArrowResultError {
     #[error("{0}")]
     QueryNextError(&'static str),
+    #[error("duckdb schema error: {0}")]
+    SchemaError(String),
     #[error(transparent)]
     ArrowError(#[from] ArrowError),
+    #[error(transparent)]
+    LogicalTypeError(#[from] LogicalTypeError),
 }
 
 impl From<ArrowResultHandle> for ArrowResult {
     fn from(handle: ArrowResultHandle) -> Self {
-        Self { handle }
+        Self {
+            handle,
+            schema: OnceLock::new(),
+        }
     }
 }
 
@@ -52,19 +73,42 @@ impl ArrowResult {
     pub fn rows_changed(&self) -> u64 {
         unsafe { ffi::duckdb_arrow_rows_changed(**self) }
     }
-    pub fn into_stream(self) -> Result<ArrowArrayStreamReader, ArrowResultError> {
-        let stream = FFI_ArrowArrayStream {
-            get_schema: Some(get_schema),
-            get_next: Some(get_next),
-            get_last_error: Some(get_last_error),
-            release: Some(release),
-            private_data: Box::into_raw(Box::new(StreamData {
-                result: self,
-                duckdb_error: false,
-            }))
-            .cast(),
+    /// Arrow schema of the result. Does not consume any rows.
+    pub fn schema(&self) -> Result<SchemaRef, ArrowResultError> {
+        if let Some(schema) = self.schema.get() {
+            return Ok(schema.clone());
+        }
+        let mut ffi_schema = FFI_ArrowSchema::empty();
+        let r = unsafe {
+            ffi::duckdb_query_arrow_schema(
+                **self,
+                &mut std::ptr::addr_of_mut!(ffi_schema) as *mut _ as *mut ffi::duckdb_arrow_schema,
+            )
         };
-        Ok(ArrowArrayStreamReader::try_new(stream)?)
+        if r != ffi::DuckDBSuccess {
+            return Err(ArrowResultError::SchemaError(unsafe { self.error() }));
+        }
+        let schema = Arc::new(Schema::try_from(&ffi_schema)?);
+        Ok(self.schema.get_or_init(|| schema).clone())
+    }
+    pub fn column_names(&self) -> Result<Vec<String>, ArrowResultError> {
+        let schema = self.schema()?;
+        Ok(schema.fields().iter().map(|f| f.name().clone()).collect())
+    }
+    /// Column types, derived from the Arrow schema.
+    ///
+    /// `ENUM` columns are reported as `VARCHAR`, since Arrow dictionaries do not carry members.
+    pub fn column_types(&self) -> Result<Vec<LogicalType>, ArrowResultError> {
+        let schema = self.schema()?;
+        let types = schema
+            .fields()
+            .iter()
+            .map(|f| LogicalType::from_arrow_field(f))
+            .collect::<Result<_, _>>()?;
+        Ok(types)
+    }
+    pub fn into_stream(self) -> Result<ArrowArrayStreamReader, ArrowResultError> {
+        new_stream(StreamSource::Materialized(self))
     }
 }
 
@@ -76,60 +120,277 @@ impl Deref for ArrowResult {
     }
 }
 
+/// Result of a query executed in streaming mode.
+///
+/// Chunks are fetched from duckdb and converted to Arrow only as the stream is read, so the
+/// result is never materialized. Any other query on the connection closes the result, and
+/// reading it further then fails.
+#[derive(Debug)]
+pub struct StreamingResult {
+    pub handle: QueryResultHandle,
+    schema: OnceLock<SchemaRef>,
+    /// Query count of the connection when the result was produced
+    query: u64,
+}
+
+impl From<QueryResultHandle> for StreamingResult {
+    fn from(handle: QueryResultHandle) -> Self {
+        let query = handle.connection().query_count();
+        Self {
+            handle,
+            schema: OnceLock::new(),
+            query,
+        }
+    }
+}
+
+impl StreamingResult {
+    pub fn column_count(&self) -> u64 {
+        unsafe { ffi::duckdb_column_count(self.handle.as_ptr()) }
+    }
+    /// Arrow schema of the result, derived from the column types
+    pub fn schema(&self) -> Result<SchemaRef, ArrowResultError> {
+        if let Some(schema) = self.schema.get() {
+            return Ok(schema.clone());
+        }
+        let columns = (0..self.column_count())
+            .map(|i| unsafe {
+                let name = ffi::duckdb_column_name(self.handle.as_ptr(), i);
+                let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+                let type_ = ffi::duckdb_column_logical_type(self.handle.as_ptr(), i);
+                (name, LogicalType::from(LogicalTypeHandle::from_raw(type_)))
+            })
+            .collect::<Vec<_>>();
+        let schema = Arc::new(to_arrow_schema(&columns)?);
+        Ok(self.schema.get_or_init(|| schema).clone())
+    }
+    pub fn column_names(&self) -> Result<Vec<String>, ArrowResultError> {
+        let schema = self.schema()?;
+        Ok(schema.fields().iter().map(|f| f.name().clone()).collect())
+    }
+    pub fn into_stream(self) -> Result<ArrowArrayStreamReader, ArrowResultError> {
+        new_stream(StreamSource::Streaming(self))
+    }
+    /// Whether another query ran on the connection since, which closes the result
+    pub fn is_closed(&self) -> bool {
+        self.handle.connection().query_count() != self.query
+    }
+}
+
+enum StreamSource {
+    Materialized(ArrowResult),
+    Streaming(StreamingResult),
+}
+
+impl StreamSource {
+    fn schema(&self) -> Result<SchemaRef, ArrowResultError> {
+        match self {
+            StreamSource::Materialized(result) => result.schema(),
+            StreamSource::Streaming(result) => result.schema(),
+        }
+    }
+    /// Write the next batch to `out`. At the end of the stream, `out` is left released.
+    unsafe fn next(&self, out: &mut FFI_ArrowArray) -> Result<(), ()> {
+        match self {
+            StreamSource::Materialized(result) => {
+                let r = ffi::duckdb_query_arrow_array(
+                    **result,
+                    &mut (out as *mut FFI_ArrowArray) as *mut _ as *mut ffi::duckdb_arrow_array,
+                );
+                if r != ffi::DuckDBSuccess {
+                    return Err(());
+                }
+                Ok(())
+            }
+            StreamSource::Streaming(result) => {
+                let chunk = ffi::duckdb_stream_fetch_chunk(*result.handle);
+                if chunk.is_null() {
+                    // Exhausted, failed, or closed by another query without an error
+                    return match result.handle.error() {
+                        Some(_) => Err(()),
+                        None if result.is_closed() => Err(()),
+                        None => Ok(()),
+                    };
+                }
+                let chunk = DataChunkHandle::from_raw(chunk);
+                ffi::duckdb_result_arrow_array(
+                    *result.handle,
+                    *chunk,
+                    &mut (out as *mut FFI_ArrowArray) as *mut _ as *mut ffi::duckdb_arrow_array,
+                );
+                Ok(())
+            }
+        }
+    }
+    unsafe fn error(&self) -> *const c_char {
+        match self {
+            StreamSource::Materialized(result) => ffi::duckdb_query_arrow_error(**result),
+            StreamSource::Streaming(result) => {
+                let err = ffi::duckdb_result_error(result.handle.as_ptr());
+                if err.is_null() && result.is_closed() {
+                    return cstr!("streaming result was closed by another query on its connection")
+                        .as_ptr();
+                }
+                err
+            }
+        }
+    }
+}
+
+fn new_stream(source: StreamSource) -> Result<ArrowArrayStreamReader, ArrowResultError> {
+    let stream = FFI_ArrowArrayStream {
+        get_schema: Some(get_schema),
+        get_next: Some(get_next),
+        get_last_error: Some(get_last_error),
+        release: Some(release),
+        private_data: Box::into_raw(Box::new(StreamData {
+            source,
+            duckdb_error: false,
+            callback_error: None,
+            span: span!("arrow_stream"),
+            rows: 0,
+            finished: false,
+        }))
+        .cast(),
+    };
+    Ok(ArrowArrayStreamReader::try_new(stream)?)
+}
+
 struct StreamData {
-    result: ArrowResult,
+    source: StreamSource,
     duckdb_error: bool,
+    /// Message of a panic or a non-duckdb error inside a stream callback
+    callback_error: Option<CString>,
+    /// Covers the stream from creation to release
+    span: Span,
+    rows: u64,
+    /// Set at the end of the stream, which later calls keep reporting
+    finished: bool,
 }
 
 unsafe extern "C" fn get_schema(
     stream: *mut FFI_ArrowArrayStream,
     out: *mut FFI_ArrowSchema,
 ) -> i32 {
-    assert!(out != ptr::null_mut());
-    let stream_data: *const StreamData = (*stream).private_data.cast();
-    let res = *(*stream_data).result;
-    let mut out_schema = FFI_ArrowSchema::empty();
-    if ffi::duckdb_query_arrow_schema(
-        res,
-        &mut std::ptr::addr_of_mut!(out_schema) as *mut _ as *mut ffi::duckdb_arrow_schema,
-    ) != ffi::DuckDBSuccess
-    {
+    let stream_data: *mut StreamData = (*stream).private_data.cast();
+    let result = catch_panic(|| {
+        assert!(!out.is_null());
+        // Reuse the cached schema rather than asking duckdb again
+        let schema = match (*stream_data).source.schema() {
+            Ok(schema) => schema,
+            Err(e) => {
+                (*stream_data).callback_error = Some(error_cstring(e.to_string()));
+                return libc::EIO;
+            }
+        };
+        match FFI_ArrowSchema::try_from(schema.as_ref()) {
+            Ok(ffi_schema) => {
+                std::ptr::write(out, ffi_schema);
+                0
+            }
+            Err(e) => {
+                (*stream_data).callback_error = Some(error_cstring(e.to_string()));
+                libc::EIO
+            }
+        }
+    });
+    result.unwrap_or_else(|e| {
+        (*stream_data).callback_error = Some(error_cstring(e));
         libc::EIO
-    } else {
-        *out = out_schema;
-        0
-    }
+    })
 }
 
 unsafe extern "C" fn get_next(stream: *mut FFI_ArrowArrayStream, out: *mut FFI_ArrowArray) -> i32 {
     let stream_data: *mut StreamData = (*stream).private_data.cast();
-    let res = *(*stream_data).result;
-    let mut out_array = FFI_ArrowArray::empty();
-    if ffi::duckdb_query_arrow_array(
-        res,
-        &mut std::ptr::addr_of_mut!(out_array) as *mut _ as *mut ffi::duckdb_arrow_array,
-    ) != ffi::DuckDBSuccess
-    {
-        (*stream_data).duckdb_error = true;
+    let result = catch_panic(|| {
+        let mut out_array = FFI_ArrowArray::empty();
+        let data = &mut *stream_data;
+        if data.finished {
+            *out = out_array;
+            return 0;
+        }
+        match data.span.in_scope(|| data.source.next(&mut out_array)) {
+            Ok(()) => {
+                data.rows += out_array.len() as u64;
+                data.finished = out_array.is_released();
+                *out = out_array;
+                0
+            }
+            Err(()) => {
+                (*stream_data).duckdb_error = true;
+                libc::EIO
+            }
+        }
+    });
+    result.unwrap_or_else(|e| {
+        (*stream_data).callback_error = Some(error_cstring(e));
         libc::EIO
-    } else {
-        *out = out_array;
-        0
-    }
+    })
 }
 
 unsafe extern "C" fn get_last_error(stream: *mut FFI_ArrowArrayStream) -> *const c_char {
     let stream_data: *const StreamData = (*stream).private_data.cast();
-    let res = *(*stream_data).result;
-    if (*stream_data).duckdb_error {
-        let ptr = ffi::duckdb_query_arrow_error(res);
-        assert!(ptr != ptr::null());
-        ptr
-    } else {
+    let result = catch_panic(|| {
+        if let Some(e) = &(*stream_data).callback_error {
+            return e.as_ptr();
+        }
+        if (*stream_data).duckdb_error {
+            let ptr = (*stream_data).source.error();
+            if !ptr.is_null() {
+                return ptr;
+            }
+        }
         cstr!("unknown error occured").as_ptr()
-    }
+    });
+    result.unwrap_or_else(|_| cstr!("panic while retrieving error").as_ptr())
 }
 
 unsafe extern "C" fn release(stream: *mut FFI_ArrowArrayStream) {
-    drop::<Box<StreamData>>(Box::from_raw((*stream).private_data.cast()))
+    let private_data = (*stream).private_data;
+    let _ = catch_panic(|| {
+        let data = *Box::<StreamData>::from_raw(private_data.cast());
+        data.span.record_rows(data.rows);
+        let error = match &data.callback_error {
+            Some(e) => Err(e.to_string_lossy().into_owned()),
+            None if data.duckdb_error => {
+                let e = data.source.error();
+                Err(if e.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(e).to_string_lossy().into_owned()
+                })
+            }
+            None => Ok(()),
+        };
+        let _ = data.span.finish(error);
+    });
+    (*stream).release = None;
+}
+
+pub(crate) fn strings(batch: &RecordBatch, column: usize) -> &StringArray {
+    batch.column(column).as_string::<i32>()
+}
+
+pub(crate) fn booleans(batch: &RecordBatch, column: usize) -> &BooleanArray {
+    batch.column(column).as_boolean()
+}
+
+pub(crate) fn lists(batch: &RecordBatch, column: usize) -> &ListArray {
+    batch.column(column).as_list::<i32>()
+}
+
+pub(crate) fn optional(array: &StringArray, row: usize) -> Option<String> {
+    array.is_valid(row).then(|| array.value(row).to_owned())
+}
+
+pub(crate) fn string_list(array: &ListArray, row: usize) -> Vec<String> {
+    if array.is_null(row) {
+        return Vec::new();
+    }
+    let values = array.value(row);
+    values
+        .as_string::<i32>()
+        .iter()
+        .map(|s| s.unwrap_or_default().to_owned())
+        .collect()
 }
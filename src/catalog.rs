@@ -0,0 +1,459 @@
+use std::collections::HashMap;
+
+use arrow::{error::ArrowError, record_batch::RecordBatch};
+use thiserror::Error;
+
+use crate::{
+    arrow::{booleans, lists, optional, string_list, strings, ArrowResultError},
+    connection::{Connection, ConnectionError},
+    sql::quote_qualified,
+    statement::Params,
+    types::LogicalType,
+};
+
+/// Schemas, tables, views and functions visible to a connection
+#[derive(Debug, Clone, Copy)]
+pub struct Catalog<'a> {
+    connection: &'a Connection,
+}
+
+#[derive(Error, Debug)]
+pub enum CatalogError {
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+    #[error(transparent)]
+    ArrowResult(#[from] ArrowResultError),
+    #[error(transparent)]
+    Arrow(#[from] ArrowError),
+    #[error("table not found: {0}")]
+    TableNotFound(String),
+    #[error("table {table} has {found} columns, the catalog lists {expected}")]
+    ColumnCountMismatch {
+        table: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaInfo {
+    pub database: String,
+    pub name: String,
+    /// Whether the schema is built in
+    pub internal: bool,
+}
+
+#[derive(Debug)]
+pub struct TableInfo {
+    pub database: String,
+    pub schema: String,
+    pub name: String,
+    pub temporary: bool,
+    pub columns: Vec<ColumnInfo>,
+    pub constraints: Vec<ConstraintInfo>,
+}
+
+#[derive(Debug)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub logical_type: LogicalType,
+    pub nullable: bool,
+    /// Default expression, if any
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintKind {
+    PrimaryKey,
+    Unique,
+    ForeignKey,
+    Check,
+    NotNull,
+    Other(String),
+}
+
+impl From<&str> for ConstraintKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "PRIMARY KEY" => Self::PrimaryKey,
+            "UNIQUE" => Self::Unique,
+            "FOREIGN KEY" => Self::ForeignKey,
+            "CHECK" => Self::Check,
+            "NOT NULL" => Self::NotNull,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintInfo {
+    pub kind: ConstraintKind,
+    /// Columns the constraint applies to
+    pub columns: Vec<String>,
+    /// Definition of the constraint, e.g. `CHECK((x > 0))`
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewInfo {
+    pub database: String,
+    pub schema: String,
+    pub name: String,
+    pub temporary: bool,
+    pub sql: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    pub database: String,
+    pub schema: String,
+    pub name: String,
+    /// e.g. `scalar`, `aggregate`, `table` or `macro`
+    pub function_type: String,
+    pub parameters: Vec<String>,
+    /// Parameter types as SQL text
+    pub parameter_types: Vec<String>,
+    pub return_type: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Database, schema and name of a table
+type TableKey = (String, String, String);
+
+impl Connection {
+    pub fn catalog(&self) -> Catalog<'_> {
+        Catalog { connection: self }
+    }
+}
+
+impl<'a> Catalog<'a> {
+    pub fn schemas(&self) -> Result<Vec<SchemaInfo>, CatalogError> {
+        let batches = self.batches(
+            "SELECT database_name, schema_name, internal FROM duckdb_schemas() \
+             ORDER BY database_name, schema_name",
+            (),
+        )?;
+        let mut schemas = Vec::new();
+        for batch in &batches {
+            let (database, name) = (strings(batch, 0), strings(batch, 1));
+            let internal = booleans(batch, 2);
+            for row in 0..batch.num_rows() {
+                schemas.push(SchemaInfo {
+                    database: database.value(row).to_owned(),
+                    name: name.value(row).to_owned(),
+                    internal: internal.value(row),
+                });
+            }
+        }
+        Ok(schemas)
+    }
+
+    /// User tables of a schema, or of all schemas if `schema` is `None`
+    pub fn tables(&self, schema: Option<&str>) -> Result<Vec<TableInfo>, CatalogError> {
+        self.table_infos(
+            "SELECT database_name, schema_name, table_name, temporary FROM duckdb_tables() \
+             WHERE NOT internal AND schema_name = coalesce(?, schema_name) \
+             ORDER BY database_name, schema_name, table_name",
+            (schema,),
+        )
+    }
+
+    /// A table of `schema`, or of the current schema if `None`.
+    ///
+    /// Tables of the current database are preferred over temporary tables of the same name.
+    pub fn table(&self, schema: Option<&str>, name: &str) -> Result<TableInfo, CatalogError> {
+        self.table_infos(
+            "SELECT database_name, schema_name, table_name, temporary FROM duckdb_tables() \
+             WHERE schema_name = coalesce(?, current_schema()) AND table_name = ? \
+             ORDER BY database_name = current_database() DESC LIMIT 1",
+            (schema, name),
+        )?
+        .pop()
+        .ok_or_else(|| CatalogError::TableNotFound(name.to_owned()))
+    }
+
+    /// User views of all schemas
+    pub fn views(&self) -> Result<Vec<ViewInfo>, CatalogError> {
+        let batches = self.batches(
+            "SELECT database_name, schema_name, view_name, temporary, sql FROM duckdb_views() \
+             WHERE NOT internal ORDER BY database_name, schema_name, view_name",
+            (),
+        )?;
+        let mut views = Vec::new();
+        for batch in &batches {
+            let (database, schema, name) =
+                (strings(batch, 0), strings(batch, 1), strings(batch, 2));
+            let (temporary, sql) = (booleans(batch, 3), strings(batch, 4));
+            for row in 0..batch.num_rows() {
+                views.push(ViewInfo {
+                    database: database.value(row).to_owned(),
+                    schema: schema.value(row).to_owned(),
+                    name: name.value(row).to_owned(),
+                    temporary: temporary.value(row),
+                    sql: optional(sql, row),
+                });
+            }
+        }
+        Ok(views)
+    }
+
+    /// Built-in and user defined functions, including macros
+    pub fn functions(&self) -> Result<Vec<FunctionInfo>, CatalogError> {
+        let batches = self.batches(
+            "SELECT database_name, schema_name, function_name, function_type, parameters, \
+             parameter_types, return_type, description FROM duckdb_functions() \
+             ORDER BY function_name",
+            (),
+        )?;
+        let mut functions = Vec::new();
+        for batch in &batches {
+            let (database, schema, name) =
+                (strings(batch, 0), strings(batch, 1), strings(batch, 2));
+            let function_type = strings(batch, 3);
+            let (parameters, parameter_types) = (lists(batch, 4), lists(batch, 5));
+            let (return_type, description) = (strings(batch, 6), strings(batch, 7));
+            for row in 0..batch.num_rows() {
+                functions.push(FunctionInfo {
+                    database: database.value(row).to_owned(),
+                    schema: schema.value(row).to_owned(),
+                    name: name.value(row).to_owned(),
+                    function_type: function_type.value(row).to_owned(),
+                    parameters: string_list(parameters, row),
+                    parameter_types: string_list(parameter_types, row),
+                    return_type: optional(return_type, row),
+                    description: optional(description, row),
+                });
+            }
+        }
+        Ok(functions)
+    }
+
+    /// Tables selected by `tables`, a query of `database_name`, `schema_name`, `table_name`
+    /// and `temporary`, with their columns and constraints.
+    ///
+    /// Columns and constraints of all tables are fetched at once, by joining their catalog
+    /// functions with `tables`. Column types are then read from each table.
+    fn table_infos<P: Params + Copy>(
+        &self,
+        tables: &str,
+        params: P,
+    ) -> Result<Vec<TableInfo>, CatalogError> {
+        let mut infos = Vec::new();
+        for batch in &self.batches(tables, params)? {
+            let (database, schema, name) =
+                (strings(batch, 0), strings(batch, 1), strings(batch, 2));
+            let temporary = booleans(batch, 3);
+            for row in 0..batch.num_rows() {
+                infos.push(TableInfo {
+                    database: database.value(row).to_owned(),
+                    schema: schema.value(row).to_owned(),
+                    name: name.value(row).to_owned(),
+                    temporary: temporary.value(row),
+                    columns: Vec::new(),
+                    constraints: Vec::new(),
+                });
+            }
+        }
+        if infos.is_empty() {
+            return Ok(infos);
+        }
+        let mut columns = self.columns(tables, params, &infos)?;
+        let mut constraints = self.constraints(tables, params)?;
+        for info in &mut infos {
+            let key = (
+                info.database.clone(),
+                info.schema.clone(),
+                info.name.clone(),
+            );
+            info.columns = columns.remove(&key).unwrap_or_default();
+            info.constraints = constraints.remove(&key).unwrap_or_default();
+        }
+        Ok(infos)
+    }
+
+    /// Columns of the tables selected by `tables`, grouped by table
+    fn columns<P: Params>(
+        &self,
+        tables: &str,
+        params: P,
+        infos: &[TableInfo],
+    ) -> Result<HashMap<TableKey, Vec<ColumnInfo>>, CatalogError> {
+        let batches = self.batches(
+            &format!(
+                "SELECT database_name, schema_name, table_name, column_name, is_nullable, \
+                 column_default FROM ({tables}) JOIN duckdb_columns() \
+                 USING (database_name, schema_name, table_name) \
+                 ORDER BY database_name, schema_name, table_name, column_index"
+            ),
+            params,
+        )?;
+        let mut rows: HashMap<TableKey, Vec<(String, bool, Option<String>)>> = HashMap::new();
+        for batch in &batches {
+            let (database, schema, table) =
+                (strings(batch, 0), strings(batch, 1), strings(batch, 2));
+            let (name, nullable, default) =
+                (strings(batch, 3), booleans(batch, 4), strings(batch, 5));
+            for row in 0..batch.num_rows() {
+                let key = (
+                    database.value(row).to_owned(),
+                    schema.value(row).to_owned(),
+                    table.value(row).to_owned(),
+                );
+                rows.entry(key).or_default().push((
+                    name.value(row).to_owned(),
+                    nullable.value(row),
+                    optional(default, row),
+                ));
+            }
+        }
+        let mut columns = HashMap::new();
+        for info in infos {
+            let key = (
+                info.database.clone(),
+                info.schema.clone(),
+                info.name.clone(),
+            );
+            let table_rows = rows.remove(&key).unwrap_or_default();
+            // duckdb_columns() only has type names, so types are read from an empty scan
+            let scan = quote_qualified(&[&info.database, &info.schema, &info.name]);
+            let types = self
+                .connection
+                .query_result(&format!("SELECT * FROM {scan} LIMIT 0"))?;
+            let (expected, found) = (table_rows.len(), types.column_count() as usize);
+            let mismatch = || CatalogError::ColumnCountMismatch {
+                table: info.name.clone(),
+                expected,
+                found,
+            };
+            if expected != found {
+                return Err(mismatch());
+            }
+            let table_columns = table_rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, (name, nullable, default))| {
+                    Ok(ColumnInfo {
+                        name,
+                        logical_type: types.column_type(i as u64).ok_or_else(mismatch)?,
+                        nullable,
+                        default,
+                    })
+                })
+                .collect::<Result<_, CatalogError>>()?;
+            columns.insert(key, table_columns);
+        }
+        Ok(columns)
+    }
+
+    /// Constraints of the tables selected by `tables`, grouped by table
+    fn constraints<P: Params>(
+        &self,
+        tables: &str,
+        params: P,
+    ) -> Result<HashMap<TableKey, Vec<ConstraintInfo>>, CatalogError> {
+        let batches = self.batches(
+            &format!(
+                "SELECT database_name, schema_name, table_name, constraint_type, \
+                 constraint_column_names, constraint_text \
+                 FROM ({tables}) JOIN duckdb_constraints() \
+                 USING (database_name, schema_name, table_name) \
+                 ORDER BY database_name, schema_name, table_name, constraint_index"
+            ),
+            params,
+        )?;
+        let mut constraints: HashMap<TableKey, Vec<ConstraintInfo>> = HashMap::new();
+        for batch in &batches {
+            let (database, schema, table) =
+                (strings(batch, 0), strings(batch, 1), strings(batch, 2));
+            let (kind, columns, text) = (strings(batch, 3), lists(batch, 4), strings(batch, 5));
+            for row in 0..batch.num_rows() {
+                let key = (
+                    database.value(row).to_owned(),
+                    schema.value(row).to_owned(),
+                    table.value(row).to_owned(),
+                );
+                constraints.entry(key).or_default().push(ConstraintInfo {
+                    kind: kind.value(row).into(),
+                    columns: string_list(columns, row),
+                    text: optional(text, row),
+                });
+            }
+        }
+        Ok(constraints)
+    }
+
+    fn batches<P: Params>(&self, sql: &str, params: P) -> Result<Vec<RecordBatch>, CatalogError> {
+        let stream = self.connection.query_with(sql, params)?.into_stream()?;
+        Ok(stream.collect::<Result<_, ArrowError>>()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quackdb_internal::type_id::TypeId;
+
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_catalog() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query(
+            "CREATE SCHEMA s; \
+             CREATE TABLE s.\"my table\"(id INTEGER PRIMARY KEY, name VARCHAR DEFAULT 'x', \
+             score DECIMAL(10, 2) CHECK (score > 0), tags VARCHAR[]); \
+             CREATE TABLE s.other(x DOUBLE UNIQUE); \
+             CREATE VIEW s.v AS SELECT id FROM s.\"my table\"; \
+             CREATE MACRO add_one(x) AS x + 1;",
+        )?;
+        let catalog = conn.catalog();
+        assert!(catalog
+            .schemas()?
+            .iter()
+            .any(|s| s.name == "s" && !s.internal));
+
+        let tables = catalog.tables(Some("s"))?;
+        let names: Vec<_> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["my table", "other"]);
+        assert_eq!(tables[0].columns.len(), 4);
+        assert_eq!(tables[1].columns.len(), 1);
+        assert_eq!(
+            tables[1].columns[0].logical_type.type_id(),
+            Some(TypeId::Double)
+        );
+        assert_eq!(tables[1].constraints.len(), 1);
+        assert_eq!(tables[1].constraints[0].kind, ConstraintKind::Unique);
+        let table = catalog.table(Some("s"), "my table")?;
+        assert_eq!(table.name, "my table");
+        let names: Vec<_> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "score", "tags"]);
+        assert!(!table.columns[0].nullable);
+        assert_eq!(table.columns[1].default.as_deref(), Some("'x'"));
+        assert_eq!(table.columns[2].logical_type.decimal_width(), 10);
+        assert_eq!(table.columns[3].logical_type.type_id(), Some(TypeId::List));
+        assert!(table
+            .constraints
+            .iter()
+            .any(|c| c.kind == ConstraintKind::PrimaryKey && c.columns == ["id"]));
+        assert!(table
+            .constraints
+            .iter()
+            .any(|c| c.kind == ConstraintKind::Check && c.columns == ["score"]));
+        assert!(matches!(
+            catalog.table(None, "missing"),
+            Err(CatalogError::TableNotFound(_))
+        ));
+
+        let views = catalog.views()?;
+        assert_eq!(views.len(), 1);
+        assert_eq!(views[0].name, "v");
+        let functions = catalog.functions()?;
+        let add_one = functions.iter().find(|f| f.name == "add_one").unwrap();
+        assert_eq!(add_one.function_type, "macro");
+        assert_eq!(add_one.parameters, ["x"]);
+        assert!(functions.iter().any(|f| f.name == "sum"));
+        Ok(())
+    }
+}
@@ -41,6 +41,14 @@ impl Config {
         }
         Ok(self)
     }
+    /// Allow loading extension files without a valid signature
+    pub fn allow_unsigned_extensions(&mut self, allow: bool) -> Result<&mut Config, ConfigError> {
+        self.set("allow_unsigned_extensions", allow)
+    }
+    /// Directory `INSTALL` writes extensions to and `LOAD` looks them up in
+    pub fn extension_directory(&mut self, path: &str) -> Result<&mut Config, ConfigError> {
+        self.set("extension_directory", path)
+    }
 }
 
 impl Deref for Config {
@@ -1,27 +1,62 @@
 use std::{
+    cell::{Cell, RefCell},
+    convert::Infallible,
     ffi::{CStr, CString},
+    marker::PhantomData,
     ops::Deref,
-    sync::Arc,
+    sync::{Arc, Mutex, PoisonError},
 };
 
+use arrow::record_batch::RecordBatchReader;
 use libc::c_void;
 use quackdb_internal::{
     ffi,
-    handles::{AppenderHandle, ArrowResultHandle, ConnectionHandle, PreparedStatementHandle},
+    handles::{
+        AppenderHandle, ArrowResultHandle, ConnectionHandle, PreparedStatementHandle,
+        QueryResultHandle,
+    },
 };
 
 use crate::{
-    appender::Appender,
-    arrow::ArrowResult,
-    statement::PreparedStatement,
-    table_function::{BindInfo, ExtraInfo, FunctionInfo, InitInfo},
+    appender::{Appender, ColumnInfo},
+    arrow::{ArrowResult, StreamingResult},
+    data_chunk::{vector_size, DataChunk, Row},
+    database::DatabaseError,
+    panic::{catch_panic, error_cstring},
+    query_result::{QueryResult, QueryResultError},
+    sql::redact_literals,
+    statement::{is_ddl, Params, PreparedStatement, PreparedStatementError, StatementCache},
+    table_function::{
+        arrow_bind, arrow_init, arrow_scan, parallel_scan, ArrowBindData, ArrowScan, BindData,
+        BindInfo, ExtraInfo, FunctionInfo, InitData, InitInfo, LocalScan, ParallelTableFunction,
+        Parameters, Projection, WorkQueue,
+    },
+    trace::span,
+    types::LogicalType,
 };
 
+/// A connection to a [`Database`](crate::database::Database).
+///
+/// A connection can be moved to another thread, but not shared between threads, since a
+/// query from one thread would invalidate results another thread is reading. Use
+/// [`try_clone`](Self::try_clone) to get a connection for each thread.
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<quackdb::connection::Connection>();
+/// ```
 #[derive(Debug)]
 pub struct Connection {
     handle: Arc<ConnectionHandle>,
+    statement_cache: RefCell<StatementCache>,
+    _not_sync: PhantomData<Cell<()>>,
 }
 
+// SAFETY: the connection handle is `Send`. Cached statements are shared only with
+// `PreparedStatement`s, which are not `Send`, and duckdb serializes their use with the
+// connection's own calls.
+unsafe impl Send for Connection {}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ConnectionError {
     #[error("bad query: {0}")]
@@ -36,11 +71,23 @@ pub enum ConnectionError {
     PrepareError(String),
     #[error("appender error: {0}")]
     AppenderError(String),
+    #[error("bad function name: {0}")]
+    BadFunctionName(String),
+    #[error("bad column name: {0}")]
+    BadColumnName(String),
+    #[error("{0} column names given for {1} columns")]
+    ColumnCountMismatch(usize, usize),
+    #[error("failed to register table function `{0}`")]
+    RegisterTableFunctionError(String),
 }
 
 impl From<Arc<ConnectionHandle>> for Connection {
     fn from(value: Arc<ConnectionHandle>) -> Self {
-        Self { handle: value }
+        Self {
+            handle: value,
+            statement_cache: RefCell::default(),
+            _not_sync: PhantomData,
+        }
     }
 }
 
@@ -50,65 +97,271 @@ impl Connection {
     }
 
     pub fn query_progress(&self) -> f64 {
-        unsafe { ffi::duckdb_query_progress(**self) }
+        unsafe { ffi::duckdb_query_progress(**self).percentage }
     }
 
     /// Perform a query and return the handle.
     pub fn query(&self, query: &str) -> Result<ArrowResult, ConnectionError> {
-        let cstr = CString::new(query).map_err(|_| ConnectionError::BadQuery(query.to_owned()))?;
-        unsafe {
-            let mut result: ffi::duckdb_arrow = std::mem::zeroed();
-            let r = ffi::duckdb_query_arrow(**self, cstr.as_ptr(), &mut result);
-            let h: ArrowResult =
-                ArrowResultHandle::from_raw_connection(result, self.handle.clone()).into();
-            if r != ffi::DuckDBSuccess {
-                return Err(ConnectionError::QueryError(h.error()));
+        self.invalidate_on_ddl(query);
+        let span = span!("query", sql = redact_literals(query));
+        let result = span.in_scope(|| -> Result<_, ConnectionError> {
+            let cstr =
+                CString::new(query).map_err(|_| ConnectionError::BadQuery(query.to_owned()))?;
+            unsafe {
+                let mut result: ffi::duckdb_arrow = std::mem::zeroed();
+                self.handle.begin_query();
+                let r = ffi::duckdb_query_arrow(**self, cstr.as_ptr(), &mut result);
+                let h: ArrowResult =
+                    ArrowResultHandle::from_raw_connection(result, self.handle.clone()).into();
+                if r != ffi::DuckDBSuccess {
+                    return Err(ConnectionError::QueryError(h.error()));
+                }
+                Ok(h)
             }
-            Ok(h)
+        });
+        if let Ok(result) = &result {
+            span.record_rows(result.row_count());
         }
+        span.finish(result)
+    }
+
+    /// Perform a query and return a result readable without Arrow
+    pub fn query_result(&self, query: &str) -> Result<QueryResult, ConnectionError> {
+        self.invalidate_on_ddl(query);
+        let span = span!("query", sql = redact_literals(query));
+        let result = span.in_scope(|| -> Result<_, ConnectionError> {
+            let cstr =
+                CString::new(query).map_err(|_| ConnectionError::BadQuery(query.to_owned()))?;
+            unsafe {
+                let mut result: ffi::duckdb_result = std::mem::zeroed();
+                self.handle.begin_query();
+                let r = ffi::duckdb_query(**self, cstr.as_ptr(), &mut result);
+                let h = QueryResultHandle::from_raw_connection(result, self.handle.clone());
+                if r != ffi::DuckDBSuccess {
+                    return Err(ConnectionError::QueryError(h.error().unwrap_or_default()));
+                }
+                Ok(QueryResult::from(h))
+            }
+        });
+        if let Ok(result) = &result {
+            span.record_rows(result.row_count());
+        }
+        span.finish(result)
+    }
+
+    /// Perform a query in streaming mode. Rows are produced while the result is read, so
+    /// large results are never fully materialized.
+    pub fn query_streaming(&self, query: &str) -> Result<StreamingResult, ConnectionError> {
+        self.prepare(query)?
+            .execute_streaming()
+            .map_err(|e| ConnectionError::QueryError(e.to_string()))
+    }
+
+    /// Perform a parameterized query, reusing a cached prepared statement
+    pub fn query_with<P: Params>(
+        &self,
+        query: &str,
+        params: P,
+    ) -> Result<ArrowResult, ConnectionError> {
+        let mut statement = self.prepare_cached(query)?;
+        let result = params
+            .bind_to(&mut statement)
+            .and_then(|_| statement.execute());
+        self.evict_on_error(query, result)
+    }
+
+    /// Run a parameterized statement and return the number of rows changed
+    pub fn execute<P: Params>(&self, query: &str, params: P) -> Result<u64, ConnectionError> {
+        let result = self.prepare_cached(query)?.execute_params(params);
+        self.evict_on_error(query, result)
+    }
+
+    /// Drop the cached statement for `query` if running it failed, as it may be stale
+    fn evict_on_error<T>(
+        &self,
+        query: &str,
+        result: Result<T, PreparedStatementError>,
+    ) -> Result<T, ConnectionError> {
+        result.map_err(|e| {
+            self.statement_cache.borrow_mut().remove(query);
+            ConnectionError::QueryError(e.to_string())
+        })
     }
 
     pub fn prepare(&self, query: &str) -> Result<PreparedStatement, ConnectionError> {
+        self.invalidate_on_ddl(query);
+        Ok(self.prepare_handle(query)?.into())
+    }
+
+    fn prepare_handle(&self, query: &str) -> Result<Arc<PreparedStatementHandle>, ConnectionError> {
+        let span = span!("prepare", sql = redact_literals(query));
+        let result = span.in_scope(|| self.prepare_raw(query));
+        span.finish(result)
+    }
+
+    fn prepare_raw(&self, query: &str) -> Result<Arc<PreparedStatementHandle>, ConnectionError> {
         let cstr = CString::new(query).map_err(|_| ConnectionError::BadQuery(query.to_owned()))?;
         unsafe {
             let mut prepare: ffi::duckdb_prepared_statement = std::mem::zeroed();
+            self.handle.begin_query();
             let res = ffi::duckdb_prepare(**self, cstr.as_ptr(), &mut prepare);
             if res != ffi::DuckDBSuccess {
                 let err = ffi::duckdb_prepare_error(prepare);
-                let err = CStr::from_ptr(err).to_string_lossy().to_owned().to_string();
+                let err = CStr::from_ptr(err).to_string_lossy().into_owned();
                 ffi::duckdb_destroy_prepare(&mut prepare);
                 return Err(ConnectionError::PrepareError(err));
             }
-            Ok(PreparedStatementHandle::from_raw(prepare, self.handle.clone()).into())
+            Ok(PreparedStatementHandle::from_raw(
+                prepare,
+                self.handle.clone(),
+                query,
+            ))
+        }
+    }
+
+    /// Prepare a statement, reusing a cached one for the same SQL text.
+    ///
+    /// The returned statement has no bindings. If the cached statement is still in use
+    /// elsewhere, a new one is prepared instead. The cache is cleared whenever this
+    /// connection runs or prepares SQL containing DDL such as `CREATE` or `ALTER`.
+    ///
+    /// Schema changes made through other connections, including [`try_clone`](Self::try_clone)
+    /// siblings, do not clear the cache. DuckDB rebinds cached statements against the new
+    /// schema when they run, and a cached statement that fails in
+    /// [`query_with`](Self::query_with) or [`execute`](Self::execute) is evicted, so the next
+    /// call prepares it again.
+    pub fn prepare_cached(&self, query: &str) -> Result<PreparedStatement, ConnectionError> {
+        if is_ddl(query) {
+            return self.prepare(query);
+        }
+        let cached = self.statement_cache.borrow_mut().get(query);
+        if let Some(handle) = cached {
+            // The cache itself holds one reference
+            if Arc::strong_count(&handle) == 2 {
+                let mut statement = PreparedStatement::from(handle);
+                statement
+                    .clear_bindings()
+                    .map_err(|e| ConnectionError::PrepareError(e.to_string()))?;
+                return Ok(statement);
+            }
+            return self.prepare(query);
+        }
+        let handle = self.prepare_handle(query)?;
+        self.statement_cache
+            .borrow_mut()
+            .insert(query, handle.clone());
+        Ok(handle.into())
+    }
+
+    /// Maximum number of statements kept by [`prepare_cached`](Self::prepare_cached)
+    pub fn statement_cache_capacity(&self) -> usize {
+        self.statement_cache.borrow().capacity()
+    }
+
+    pub fn set_statement_cache_capacity(&self, capacity: usize) {
+        self.statement_cache.borrow_mut().set_capacity(capacity)
+    }
+
+    pub fn clear_statement_cache(&self) {
+        self.statement_cache.borrow_mut().clear()
+    }
+
+    /// Open another connection to the same database, with the same statement cache capacity.
+    ///
+    /// Connections are not `Sync`, so each thread querying a database needs its own.
+    pub fn try_clone(&self) -> Result<Connection, DatabaseError> {
+        let database = self.handle.database();
+        let mut handle = std::ptr::null_mut();
+        let r = unsafe { ffi::duckdb_connect(***database, &mut handle) };
+        if r != ffi::DuckDBSuccess {
+            return Err(DatabaseError::ConnectError);
+        }
+        let connection =
+            Connection::from(unsafe { ConnectionHandle::from_raw(handle, database.clone()) });
+        connection.set_statement_cache_capacity(self.statement_cache_capacity());
+        Ok(connection)
+    }
+
+    fn invalidate_on_ddl(&self, query: &str) {
+        if is_ddl(query) {
+            self.clear_statement_cache();
         }
     }
 
     pub fn appender(&self, schema: Option<&str>, table: &str) -> Result<Appender, ConnectionError> {
-        let schema = schema
+        let c_schema = schema
             .map(|s| CString::new(s).map_err(|_| ConnectionError::BadSchema(s.to_owned())))
             .transpose()?;
-        let table = CString::new(table).map_err(|_| ConnectionError::BadTable(table.to_owned()))?;
-        unsafe {
+        let c_table =
+            CString::new(table).map_err(|_| ConnectionError::BadTable(table.to_owned()))?;
+        let handle = unsafe {
             let mut out_appender: ffi::duckdb_appender = std::mem::zeroed();
             let r = ffi::duckdb_appender_create(
                 **self,
-                schema.map_or(std::ptr::null(), |s| s.as_ptr()),
-                table.as_ptr(),
+                c_schema.map_or(std::ptr::null(), |s| s.as_ptr()),
+                c_table.as_ptr(),
                 &mut out_appender,
             );
             if r != ffi::DuckDBSuccess {
                 let err = CStr::from_ptr(ffi::duckdb_appender_error(out_appender));
                 let err = err.to_string_lossy().into_owned();
                 ffi::duckdb_appender_destroy(&mut out_appender);
-                Err(ConnectionError::AppenderError(err))
-            } else {
-                Ok(AppenderHandle::from_raw(out_appender, self.handle.clone()).into())
+                return Err(ConnectionError::AppenderError(err));
             }
-        }
+            AppenderHandle::from_raw(out_appender, self.handle.clone())
+        };
+        Ok(Appender::for_table(
+            handle,
+            schema.map(str::to_owned),
+            table.to_owned(),
+        ))
     }
 
+    /// Names and nullability of the columns of a table, which the C API does not expose.
+    ///
+    /// Like `duckdb_appender_create`, this defaults to the `main` schema and prefers a
+    /// temporary table over one of the same name in the current database.
+    pub(crate) fn appender_columns(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+    ) -> Result<Vec<ColumnInfo>, ConnectionError> {
+        let mut statement = self.prepare(
+            "WITH matches AS ( \
+                 SELECT database_name, column_name, is_nullable, column_index \
+                 FROM duckdb_columns() \
+                 WHERE database_name IN ('temp', current_database()) \
+                 AND lower(schema_name) = lower(coalesce(?, 'main')) \
+                 AND lower(table_name) = lower(?)) \
+             SELECT column_name, is_nullable FROM matches \
+             WHERE database_name = \
+                 (SELECT database_name FROM matches ORDER BY database_name <> 'temp' LIMIT 1) \
+             ORDER BY column_index",
+        )?;
+        let result = statement
+            .bind(schema)
+            .and_then(|s| s.bind(table))
+            .and_then(|s| s.execute_result())
+            .map_err(|e| ConnectionError::AppenderError(e.to_string()))?;
+        (0..result.row_count())
+            .map(|row| {
+                Ok(ColumnInfo {
+                    name: result.get_string(0, row)?.unwrap_or_default(),
+                    nullable: result.get::<bool>(1, row)?.unwrap_or(true),
+                })
+            })
+            .collect::<Result<_, QueryResultError>>()
+            .map_err(|e| ConnectionError::AppenderError(e.to_string()))
+    }
+
+    /// Register a table function under `name`, taking positional `parameters`.
+    ///
+    /// Errors and panics raised by the callbacks are reported to DuckDB as query errors.
+    #[allow(clippy::too_many_arguments)]
     pub fn register_table_function<B, I, LI, D, E>(
         &self,
+        name: &str,
+        parameters: &[LogicalType],
         bind: impl Fn(&BindInfo, &D) -> Result<B, E> + Send + 'static,
         init: impl Fn(&InitInfo, &B, &D) -> Result<I, E> + Send + 'static,
         local_init: impl Fn(&InitInfo, &B, &D) -> Result<LI, E> + Send + Sync + 'static,
@@ -126,8 +379,14 @@ impl Connection {
         D: Send + Sync,
         E: std::error::Error + Send,
     {
+        let c_name =
+            CString::new(name).map_err(|_| ConnectionError::BadFunctionName(name.to_owned()))?;
         unsafe {
-            let table_function = ffi::duckdb_create_table_function();
+            let mut table_function = ffi::duckdb_create_table_function();
+            ffi::duckdb_table_function_set_name(table_function, c_name.as_ptr());
+            for parameter in parameters {
+                ffi::duckdb_table_function_add_parameter(table_function, **parameter);
+            }
             ffi::duckdb_table_function_supports_projection_pushdown(table_function, projection);
             // Register callbacks
             ffi::duckdb_table_function_set_bind(table_function, Some(bind_fn::<B, I, LI, D, E>));
@@ -153,9 +412,141 @@ impl Connection {
                 Box::into_raw(extra).cast(),
                 Some(destroy_extra_info::<B, I, LI, D, E>),
             );
+            let r = ffi::duckdb_register_table_function(**self, table_function);
+            ffi::duckdb_destroy_table_function(&mut table_function);
+            if r != ffi::DuckDBSuccess {
+                return Err(ConnectionError::RegisterTableFunctionError(name.to_owned()));
+            }
             Ok(())
         }
     }
+
+    /// Register a [`ParallelTableFunction`] under `name`
+    pub fn register_parallel_table_function<T: ParallelTableFunction>(
+        &self,
+        name: &str,
+        function: T,
+    ) -> Result<(), ConnectionError> {
+        let projection = function.supports_projection();
+        self.register_table_function(
+            name,
+            &function.parameters(),
+            |info: &BindInfo, f: &T| f.bind(info),
+            |info: &InitInfo, bind: &T::BindData, f: &T| {
+                let partitions = f.partitions(info, bind)?;
+                info.set_max_threads(f.max_threads(partitions.len()));
+                Ok(WorkQueue::new(partitions))
+            },
+            |_: &InitInfo, _: &T::BindData, _: &T| Ok(LocalScan::default()),
+            |info: &FunctionInfo,
+             chunk: ffi::duckdb_data_chunk,
+             bind: &T::BindData,
+             queue: &WorkQueue<T::Partition>,
+             local: &LocalScan<T::ScanState>,
+             f: &T| {
+                parallel_scan(f, info, &DataChunk::from(chunk), bind, queue, local)
+            },
+            projection,
+            function,
+        )
+    }
+
+    /// Register a table function named `name` producing the rows of an iterator.
+    ///
+    /// `columns` names the result columns, whose types come from the [`Row`] implementation.
+    /// `make_iter` is called once per scan.
+    pub fn register_iter_function<R, I, F>(
+        &self,
+        name: &str,
+        columns: &[&str],
+        make_iter: F,
+    ) -> Result<(), ConnectionError>
+    where
+        R: Row + 'static,
+        I: Iterator<Item = R> + Send + 'static,
+        F: Fn() -> I + Send + Sync + 'static,
+    {
+        struct IterFunction<F> {
+            columns: Vec<CString>,
+            make_iter: F,
+        }
+        let expected = R::logical_types().len();
+        if columns.len() != expected {
+            return Err(ConnectionError::ColumnCountMismatch(
+                columns.len(),
+                expected,
+            ));
+        }
+        let columns = columns
+            .iter()
+            .map(|&c| CString::new(c).map_err(|_| ConnectionError::BadColumnName(c.to_owned())))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.register_table_function(
+            name,
+            &[],
+            |info: &BindInfo, f: &IterFunction<F>| {
+                for (name, type_) in f.columns.iter().zip(R::logical_types()) {
+                    info.add_result_column(name, &type_);
+                }
+                Ok::<_, Infallible>(())
+            },
+            |info: &InitInfo, _: &(), f: &IterFunction<F>| {
+                info.set_max_threads(1);
+                Ok(Mutex::new((f.make_iter)()))
+            },
+            |_: &InitInfo, _: &(), _: &IterFunction<F>| Ok(()),
+            |_: &FunctionInfo,
+             chunk: ffi::duckdb_data_chunk,
+             _: &(),
+             iter: &Mutex<I>,
+             _: &(),
+             _: &IterFunction<F>| {
+                let chunk = DataChunk::from(chunk);
+                let mut iter = iter.lock().unwrap_or_else(PoisonError::into_inner);
+                let mut size = 0;
+                while size < vector_size() {
+                    let Some(row) = iter.next() else { break };
+                    unsafe { row.write_row(&chunk, size) };
+                    size += 1;
+                }
+                chunk.set_size(size);
+                Ok(())
+            },
+            false,
+            IterFunction { columns, make_iter },
+        )
+    }
+
+    /// Register a table function named `name` scanning the Arrow record batches of a reader.
+    ///
+    /// `make_reader` is called with the call parameters during bind, where the result columns
+    /// are declared from the reader schema. Batches larger than the vector size are split.
+    pub fn register_arrow_function<F, E>(
+        &self,
+        name: &str,
+        parameters: &[LogicalType],
+        make_reader: F,
+    ) -> Result<(), ConnectionError>
+    where
+        F: Fn(&Parameters) -> Result<Box<dyn RecordBatchReader + Send>, E> + Send + Sync + 'static,
+        E: std::error::Error + Send + 'static,
+    {
+        self.register_table_function(
+            name,
+            parameters,
+            |info: &BindInfo, f: &F| arrow_bind(info, f),
+            |info: &InitInfo, bind: &ArrowBindData, f: &F| arrow_init(info, bind, f),
+            |_: &InitInfo, _: &ArrowBindData, _: &F| Ok(()),
+            |_: &FunctionInfo,
+             chunk: ffi::duckdb_data_chunk,
+             _: &ArrowBindData,
+             scan: &Mutex<ArrowScan>,
+             _: &(),
+             _: &F| arrow_scan(&DataChunk::from(chunk), scan),
+            false,
+            make_reader,
+        )
+    }
 }
 
 extern "C" fn bind_fn<B, I, LI, D, E>(info: ffi::duckdb_bind_info)
@@ -166,21 +557,32 @@ where
     D: Send + Sync,
     E: std::error::Error + Send,
 {
+    let span = span!("table_function_bind");
+    let result = span.in_scope(|| {
+        catch_panic(|| unsafe {
+            let extra: *const ExtraInfo<B, I, LI, D, E> =
+                ffi::duckdb_bind_get_extra_info(info).cast();
+            let f = &(*extra).bind;
+            let bind_info = BindInfo::from(info);
+            let data = f(&bind_info, &(*extra).extra).map_err(|e| e.to_string())?;
+            Ok::<_, String>(BindData {
+                data,
+                columns: bind_info.into_result_columns(),
+            })
+        })
+    });
     unsafe {
-        let extra: *const ExtraInfo<B, I, LI, E, E> = ffi::duckdb_bind_get_extra_info(info).cast();
-        let f = &(*extra).bind;
-        let result = f(&BindInfo::from(info), &(*extra).extra);
-        match result {
+        match span.finish(result.and_then(|r| r)) {
             Ok(b) => {
                 let b = Box::new(b);
                 ffi::duckdb_bind_set_bind_data(
                     info,
                     Box::into_raw(b).cast(),
-                    Some(destroy_box::<B>),
+                    Some(destroy_box::<BindData<B>>),
                 );
             }
             Err(e) => {
-                let err = CString::new(e.to_string().replace('\0', r"\0")).expect("null character");
+                let err = error_cstring(e);
                 ffi::duckdb_bind_set_error(info, err.as_ptr());
             }
         }
@@ -195,22 +597,31 @@ where
     D: Send + Sync,
     E: std::error::Error + Send,
 {
+    let span = span!("table_function_init");
+    let result = span.in_scope(|| {
+        catch_panic(|| unsafe {
+            let extra: *const ExtraInfo<B, I, LI, D, E> =
+                ffi::duckdb_init_get_extra_info(info).cast();
+            let f = &(*extra).init;
+            let bind: *const BindData<B> = ffi::duckdb_init_get_bind_data(info).cast();
+            let projection = Arc::new(Projection::from_init_info(info, (*bind).columns.clone()));
+            let init_info = InitInfo::new(info, projection.clone());
+            let data = f(&init_info, &(*bind).data, &(*extra).extra).map_err(|e| e.to_string())?;
+            Ok::<_, String>(InitData { data, projection })
+        })
+    });
     unsafe {
-        let extra: *const ExtraInfo<B, I, LI, D, E> = ffi::duckdb_init_get_extra_info(info).cast();
-        let f = &(*extra).init;
-        let bind: *const B = ffi::duckdb_init_get_bind_data(info).cast();
-        let result = f(&InitInfo::from(info), &*bind, &(*extra).extra);
-        match result {
+        match span.finish(result.and_then(|r| r)) {
             Ok(i) => {
                 let b = Box::new(i);
                 ffi::duckdb_init_set_init_data(
                     info,
                     Box::into_raw(b).cast(),
-                    Some(destroy_box::<B>),
+                    Some(destroy_box::<InitData<I>>),
                 );
             }
             Err(e) => {
-                let err = CString::new(e.to_string().replace('\0', r"\0")).expect("null character");
+                let err = error_cstring(e);
                 ffi::duckdb_init_set_error(info, err.as_ptr());
             }
         }
@@ -225,22 +636,30 @@ where
     D: Send + Sync,
     E: std::error::Error + Send,
 {
+    let span = span!("table_function_local_init");
+    let result = span.in_scope(|| {
+        catch_panic(|| unsafe {
+            let extra: *const ExtraInfo<B, I, LI, D, E> =
+                ffi::duckdb_init_get_extra_info(info).cast();
+            let bind: *const BindData<B> = ffi::duckdb_init_get_bind_data(info).cast();
+            let f = &(*extra).local_init;
+            let projection = Arc::new(Projection::from_init_info(info, (*bind).columns.clone()));
+            let init_info = InitInfo::new(info, projection);
+            f(&init_info, &(*bind).data, &(*extra).extra).map_err(|e| e.to_string())
+        })
+    });
     unsafe {
-        let extra: *const ExtraInfo<B, I, LI, D, E> = ffi::duckdb_init_get_extra_info(info).cast();
-        let bind: *const B = ffi::duckdb_init_get_bind_data(info).cast();
-        let f = &(*extra).local_init;
-        let result = f(&InitInfo::from(info), &*bind, &(*extra).extra);
-        match result {
+        match span.finish(result.and_then(|r| r)) {
             Ok(i) => {
                 let b = Box::new(i);
                 ffi::duckdb_init_set_init_data(
                     info,
                     Box::into_raw(b).cast(),
-                    Some(destroy_box::<B>),
+                    Some(destroy_box::<LI>),
                 );
             }
             Err(e) => {
-                let err = CString::new(e.to_string().replace('\0', r"\0")).expect("null character");
+                let err = error_cstring(e);
                 ffi::duckdb_init_set_error(info, err.as_ptr());
             }
         }
@@ -257,25 +676,30 @@ extern "C" fn main_fn<B, I, LI, D, E>(
     D: Send + Sync,
     E: std::error::Error + Send,
 {
-    unsafe {
-        let extra: *const ExtraInfo<B, I, LI, D, E> =
-            ffi::duckdb_function_get_extra_info(info).cast();
-        let f = &(*extra).function;
-        let bind: *const B = ffi::duckdb_function_get_bind_data(info).cast();
-        let init: *const I = ffi::duckdb_function_get_init_data(info).cast();
-        let local_init: *const LI = ffi::duckdb_function_get_local_init_data(info).cast();
-        let result = f(
-            &FunctionInfo::from(info),
-            data_chunk,
-            &*bind,
-            &*init,
-            &*local_init,
-            &(*extra).extra,
-        );
-        if let Err(e) = result {
-            let err = CString::new(e.to_string().replace('\0', r"\0")).expect("null character");
-            ffi::duckdb_function_set_error(info, err.as_ptr());
-        }
+    let span = span!("table_function_scan");
+    let result = span.in_scope(|| {
+        catch_panic(|| unsafe {
+            let extra: *const ExtraInfo<B, I, LI, D, E> =
+                ffi::duckdb_function_get_extra_info(info).cast();
+            let f = &(*extra).function;
+            let bind: *const BindData<B> = ffi::duckdb_function_get_bind_data(info).cast();
+            let init: *const InitData<I> = ffi::duckdb_function_get_init_data(info).cast();
+            let local_init: *const LI = ffi::duckdb_function_get_local_init_data(info).cast();
+            f(
+                &FunctionInfo::new(info, (*init).projection.clone()),
+                data_chunk,
+                &(*bind).data,
+                &(*init).data,
+                &*local_init,
+                &(*extra).extra,
+            )
+            .map_err(|e| e.to_string())
+        })
+    });
+    span.record_rows(unsafe { ffi::duckdb_data_chunk_get_size(data_chunk) });
+    if let Err(e) = span.finish(result.and_then(|r| r)) {
+        let err = error_cstring(e);
+        unsafe { ffi::duckdb_function_set_error(info, err.as_ptr()) };
     }
 }
 
@@ -283,8 +707,9 @@ extern "C" fn destroy_extra_info<B, I, LI, D, E>(ptr: *mut c_void) {
     destroy_box::<ExtraInfo<B, I, LI, D, E>>(ptr)
 }
 
+/// Panics from user `Drop` implementations cannot be reported anywhere, so they are discarded.
 extern "C" fn destroy_box<T>(ptr: *mut c_void) {
-    unsafe { drop::<Box<T>>(Box::from_raw(ptr.cast())) }
+    let _ = catch_panic(|| unsafe { drop::<Box<T>>(Box::from_raw(ptr.cast())) });
 }
 
 impl Deref for Connection {
@@ -297,11 +722,36 @@ impl Deref for Connection {
 
 #[cfg(test)]
 mod test {
-    use std::convert::Infallible;
+    use std::{
+        convert::Infallible,
+        ops::Range,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    use arrow::{
+        array::{Array, AsArray, Decimal128Array, Int64Array, StringArray},
+        buffer::NullBuffer,
+        datatypes::{DataType, Field, Int64Type, Schema},
+        error::ArrowError,
+        record_batch::{RecordBatch, RecordBatchIterator, RecordBatchReader},
+    };
+    use cstr::cstr;
+    use quackdb_internal::{ffi, type_id::TypeId};
 
-    use arrow::{array::AsArray, datatypes::Int64Type, error::ArrowError};
+    use crate::{
+        data_chunk::{vector_size, DataChunk},
+        database::Database,
+        error::QuackError,
+        params,
+        replacement_scan::ReplacementScanError,
+        table_function::{BindInfo, FunctionInfo, InitInfo, ParallelTableFunction, Parameters},
+        types::LogicalType,
+    };
 
-    use crate::{database::Database, error::QuackError, replacement_scan::ReplacementScanError};
+    use super::ConnectionError;
 
     #[test]
     fn test_connect() {
@@ -326,6 +776,8 @@ mod test {
             (),
         );
         conn.register_table_function(
+            "noop",
+            &[],
             |&_, &_| Ok::<(), Infallible>(()),
             |&_, &_, &_| Ok(()),
             |&_, &_, &_| Ok(()),
@@ -336,6 +788,157 @@ mod test {
 
         Ok(())
     }
+    #[test]
+    fn test_result_schema() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        let result = conn.query("SELECT 1::INTEGER AS a, 'x' AS b, 1.5::DECIMAL(4, 1) AS c")?;
+        assert_eq!(result.column_names()?, ["a", "b", "c"]);
+        let types: Vec<_> = result.column_types()?.iter().map(|t| t.type_id()).collect();
+        assert_eq!(
+            types,
+            [
+                Some(TypeId::Integer),
+                Some(TypeId::VarChar),
+                Some(TypeId::Decimal)
+            ]
+        );
+        let schema = result.schema()?;
+        assert_eq!(schema.field(2).data_type(), &DataType::Decimal128(4, 1));
+        let reader = result.into_stream()?;
+        assert_eq!(reader.schema(), schema);
+        let batches = reader.collect::<Result<Vec<_>, ArrowError>>()?;
+        assert_eq!(batches[0].num_rows(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_streaming() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        let result =
+            conn.query_streaming("SELECT range AS n, range::VARCHAR AS s FROM range(100000)")?;
+        assert_eq!(result.column_names()?, ["n", "s"]);
+        let reader = result.into_stream()?;
+        assert_eq!(reader.schema().field(1).data_type(), &DataType::Utf8);
+        let mut rows = 0;
+        let mut batches = 0;
+        for batch in reader {
+            let batch = batch?;
+            assert!(batch.num_rows() as u64 <= vector_size());
+            let n = batch.column(0).as_primitive::<Int64Type>();
+            assert_eq!(n.value(0), rows as i64);
+            rows += batch.num_rows();
+            batches += 1;
+        }
+        assert_eq!(rows, 100000);
+        assert!(batches > 1);
+        assert!(conn.query_streaming("SELECT * FROM missing_table").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_during_streaming() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        let mut reader = conn
+            .query_streaming("SELECT range AS n FROM range(100000)")?
+            .into_stream()?;
+        assert!(reader.next().unwrap().is_ok());
+        conn.query("SELECT 1")?;
+        let err = reader.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("closed by another query"), "{err}");
+
+        // a query after the stream ends leaves it ended
+        let mut reader = conn.query_streaming("SELECT 1")?.into_stream()?;
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+        conn.query("SELECT 1")?;
+        assert!(reader.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_with() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.execute("CREATE TABLE t(i INTEGER, s VARCHAR, d DOUBLE)", ())?;
+        let s = String::from("b");
+        assert_eq!(
+            conn.execute("INSERT INTO t VALUES (?, ?, ?)", (1, "a", 0.5))?,
+            1
+        );
+        assert_eq!(
+            conn.execute("INSERT INTO t VALUES (?, ?, ?)", params![2, s, None::<f64>])?,
+            1
+        );
+        assert_eq!(s, "b");
+        assert_eq!(
+            conn.execute(
+                "INSERT INTO t SELECT range, ?, ? FROM range(?, ?)",
+                ("c", 1.5, 3, 10)
+            )?,
+            7
+        );
+        assert_eq!(conn.execute("DELETE FROM t WHERE i IN (?, ?)", [8, 9])?, 2);
+        let bounds = vec![1, 5];
+        let batches = conn
+            .query_with("SELECT s FROM t WHERE i > ? AND i < ? ORDER BY i", &bounds)?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        let s: Vec<_> = batches[0].column(0).as_string::<i32>().iter().collect();
+        assert_eq!(s, [Some("b"), Some("c"), Some("c")]);
+        assert!(conn
+            .execute("INSERT INTO t VALUES (?, ?, ?)", (1,))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepare_cached() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(i INTEGER)")?;
+        let sql = "INSERT INTO t VALUES (?)";
+        let mut first = conn.prepare_cached(sql)?;
+        let cached = Arc::downgrade(&first.handle);
+        {
+            // still checked out, so a new statement is prepared
+            let second = conn.prepare_cached(sql)?;
+            assert!(!Arc::ptr_eq(&first.handle, &second.handle));
+        }
+        first.bind(1)?;
+        first.execute()?;
+        drop(first);
+
+        let second = conn.prepare_cached(sql)?;
+        assert!(Arc::ptr_eq(&second.handle, &cached.upgrade().unwrap()));
+        // bindings were cleared on checkout
+        assert!(second.execute().is_err());
+        drop(second);
+
+        conn.set_statement_cache_capacity(1);
+        conn.prepare_cached("SELECT 1")?;
+        assert!(cached.upgrade().is_none());
+
+        let cached = Arc::downgrade(&conn.prepare_cached("SELECT 1")?.handle);
+        assert!(cached.upgrade().is_some());
+        conn.query("CREATE VIEW v AS SELECT 2")?;
+        assert!(cached.upgrade().is_none());
+
+        // DDL on another connection leaves the cache alone, but a failing statement is evicted
+        let sibling = conn.try_clone()?;
+        conn.execute(sql, (1,))?;
+        let cached = Arc::downgrade(&conn.prepare_cached(sql)?.handle);
+        sibling.query("DROP TABLE t")?;
+        assert!(cached.upgrade().is_some());
+        assert!(conn.execute(sql, (2,)).is_err());
+        assert!(cached.upgrade().is_none());
+        sibling.query("CREATE TABLE t(i INTEGER)")?;
+        assert_eq!(conn.execute(sql, (3,))?, 1);
+        Ok(())
+    }
+
     #[test]
     fn test_arrow_1() -> Result<(), QuackError> {
         // Create DB
@@ -397,4 +1000,322 @@ mod test {
         assert_eq!(sum, (0..1000000i64).sum::<i64>());
         Ok(())
     }
+    #[test]
+    fn test_table_function_panic() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.register_table_function(
+            "panic_in_bind",
+            &[],
+            |&_, &_| -> Result<(), Infallible> { panic!("bind exploded") },
+            |&_, &_, &_| Ok(()),
+            |&_, &_, &_| Ok(()),
+            |&_, _, &_, &_, &_, &_| Ok(()),
+            false,
+            (),
+        )?;
+        conn.register_table_function(
+            "panic_in_scan",
+            &[],
+            |bind, &_| {
+                let ty = LogicalType::try_from(TypeId::BigInt).unwrap();
+                bind.add_result_column(cstr!("x"), &ty);
+                Ok::<(), Infallible>(())
+            },
+            |&_, &_, &_| Ok(()),
+            |&_, &_, &_| Ok(()),
+            |&_, _, &_, &_, &_, &_| panic!("scan exploded"),
+            false,
+            (),
+        )?;
+        match conn.query("SELECT * FROM panic_in_bind()") {
+            Err(ConnectionError::QueryError(e)) => assert!(e.contains("bind exploded"), "{e}"),
+            other => panic!("expected query error, got {other:?}"),
+        }
+        match conn.query("SELECT * FROM panic_in_scan()") {
+            Err(ConnectionError::QueryError(e)) => assert!(e.contains("scan exploded"), "{e}"),
+            other => panic!("expected query error, got {other:?}"),
+        }
+        // The connection is still usable afterwards
+        assert_eq!(conn.query("SELECT 42")?.row_count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_function_projection() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.register_table_function(
+            "wide",
+            &[],
+            |bind, &_| {
+                for name in [cstr!("a"), cstr!("b"), cstr!("c")] {
+                    bind.add_result_column(name, &LogicalType::try_from(TypeId::BigInt).unwrap());
+                }
+                Ok::<(), Infallible>(())
+            },
+            |init, &_, &_| {
+                let projection = init.projection();
+                assert_eq!(projection.len(), 1);
+                assert_eq!(projection.output_index_by_name("c"), Some(0));
+                assert!(!projection.is_projected(0));
+                Ok(AtomicBool::new(false))
+            },
+            |&_, &_, &_| Ok(()),
+            |info, chunk, &_, done: &AtomicBool, &_, &_| {
+                if done.swap(true, Ordering::Relaxed) {
+                    unsafe { ffi::duckdb_data_chunk_set_size(chunk, 0) };
+                    return Ok(());
+                }
+                for (output_index, column) in info.projection().iter() {
+                    assert_eq!(column.name, "c");
+                    unsafe {
+                        let vector = ffi::duckdb_data_chunk_get_vector(chunk, output_index as u64);
+                        let data: *mut i64 = ffi::duckdb_vector_get_data(vector).cast();
+                        *data = 3;
+                    }
+                }
+                unsafe { ffi::duckdb_data_chunk_set_size(chunk, 1) };
+                Ok(())
+            },
+            true,
+            (),
+        )?;
+        let batches = conn
+            .query("SELECT c FROM wide()")?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        assert_eq!(batches[0].num_columns(), 1);
+        assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().value(0), 3);
+        Ok(())
+    }
+
+    struct RangeFunction {
+        partitions: i64,
+    }
+
+    impl ParallelTableFunction for RangeFunction {
+        type BindData = ();
+        type Partition = Range<i64>;
+        type ScanState = Range<i64>;
+        type Error = Infallible;
+
+        fn bind(&self, info: &BindInfo) -> Result<(), Infallible> {
+            info.add_result_column(cstr!("x"), &LogicalType::try_from(TypeId::BigInt).unwrap());
+            Ok(())
+        }
+        fn partitions(&self, _: &InitInfo, _: &()) -> Result<Vec<Range<i64>>, Infallible> {
+            Ok((0..self.partitions)
+                .map(|i| i * 10000..(i + 1) * 10000)
+                .collect())
+        }
+        fn open(&self, _: &(), partition: &Range<i64>) -> Result<Range<i64>, Infallible> {
+            Ok(partition.clone())
+        }
+        fn scan(
+            &self,
+            _: &FunctionInfo,
+            chunk: &DataChunk,
+            _: &(),
+            _: &Range<i64>,
+            state: &mut Range<i64>,
+        ) -> Result<u64, Infallible> {
+            let vector = chunk.vector(0).unwrap();
+            let data: *mut i64 = vector.data().cast();
+            let mut rows = 0;
+            while rows < vector_size() {
+                let Some(x) = state.next() else { break };
+                unsafe { *data.add(rows as usize) = x };
+                rows += 1;
+            }
+            Ok(rows)
+        }
+    }
+
+    #[test]
+    fn test_parallel_table_function() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("SET threads TO 4")?;
+        conn.register_parallel_table_function("par_range", RangeFunction { partitions: 16 })?;
+        conn.register_parallel_table_function("par_empty", RangeFunction { partitions: 0 })?;
+        let batches = conn
+            .query("SELECT count(*), count(DISTINCT x) FROM par_range()")?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        let count = batches[0].column(0).as_primitive::<Int64Type>().value(0);
+        assert_eq!(count, 160000);
+        let distinct = batches[0].column(1).as_primitive::<Int64Type>().value(0);
+        assert_eq!(distinct, 160000);
+        let batches = conn
+            .query("SELECT count(*) FROM par_empty()")?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().value(0), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_function() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.register_iter_function("numbers", &["n", "label", "half"], || {
+            (0..5000i64).map(|i| (i, format!("#{i}"), (i % 2 == 0).then_some(i / 2)))
+        })?;
+        assert!(matches!(
+            conn.register_iter_function("bad", &["n"], || std::iter::once((1i32, 2i32))),
+            Err(ConnectionError::ColumnCountMismatch(1, 2))
+        ));
+        let batches = conn
+            .query("SELECT count(*), count(half), max(label) FROM numbers()")?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        assert_eq!(
+            batches[0].column(0).as_primitive::<Int64Type>().value(0),
+            5000
+        );
+        assert_eq!(
+            batches[0].column(1).as_primitive::<Int64Type>().value(0),
+            2500
+        );
+        assert_eq!(batches[0].column(2).as_string::<i32>().value(0), "#999");
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_function() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.register_arrow_function(
+            "months",
+            &[LogicalType::try_from(TypeId::VarChar).unwrap()],
+            |params: &Parameters| {
+                let month = params[0].to_varchar();
+                let len = vector_size() as usize * 2 + 7;
+                let schema = Arc::new(Schema::new(vec![
+                    Field::new("month", DataType::Utf8, false),
+                    Field::new("n", DataType::Int64, true),
+                ]));
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(StringArray::from(vec![month; len])),
+                        Arc::new(Int64Array::from_iter(
+                            (0..len as i64).map(|i| (i % 3 != 0).then_some(i)),
+                        )),
+                    ],
+                )?;
+                let reader = RecordBatchIterator::new([Ok(batch)], schema);
+                Ok::<_, ArrowError>(Box::new(reader) as Box<dyn RecordBatchReader + Send>)
+            },
+        )?;
+        let batches = conn
+            .query("SELECT count(*), count(n), min(month) FROM months('2024-01')")?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        let len = vector_size() as i64 * 2 + 7;
+        assert_eq!(
+            batches[0].column(0).as_primitive::<Int64Type>().value(0),
+            len
+        );
+        assert_eq!(
+            batches[0].column(1).as_primitive::<Int64Type>().value(0),
+            len - (len + 2) / 3
+        );
+        assert_eq!(batches[0].column(2).as_string::<i32>().value(0), "2024-01");
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_function_decimal_range() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.register_arrow_function(
+            "decimals",
+            &[LogicalType::try_from(TypeId::VarChar).unwrap()],
+            |params: &Parameters| {
+                // 1_000_000 does not fit the `SMALLINT` storage of `DECIMAL(4, 0)`
+                let valid = params[0].to_varchar() == "valid";
+                let values = Decimal128Array::new(
+                    vec![12, 1_000_000].into(),
+                    Some(NullBuffer::from(vec![true, valid])),
+                )
+                .with_precision_and_scale(4, 0)?;
+                let schema = Arc::new(Schema::new(vec![Field::new(
+                    "d",
+                    values.data_type().clone(),
+                    true,
+                )]));
+                let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(values)])?;
+                let reader = RecordBatchIterator::new([Ok(batch)], schema);
+                Ok::<_, ArrowError>(Box::new(reader) as Box<dyn RecordBatchReader + Send>)
+            },
+        )?;
+        let sum = conn
+            .query_result("SELECT sum(d)::BIGINT FROM decimals('null')")?
+            .get::<i64>(0, 0)?;
+        assert_eq!(sum, Some(12));
+        assert!(conn
+            .query_result("SELECT sum(d) FROM decimals('valid')")
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        assert_send::<Database>();
+        assert_sync::<Database>();
+        assert_send::<super::Connection>();
+    }
+
+    #[test]
+    fn test_multithreaded() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(thread INTEGER, i INTEGER)")?;
+        // Writers own connections cloned on this thread
+        let writers = (0..8)
+            .map(|thread| {
+                let conn = conn.try_clone()?;
+                Ok(std::thread::spawn(move || -> Result<(), QuackError> {
+                    let mut appender = conn.appender(None, "t")?;
+                    for i in 0..1000 {
+                        appender.append_row((thread, i))?;
+                    }
+                    appender.close()?;
+                    for i in 1000..1050 {
+                        conn.execute("INSERT INTO t VALUES (?, ?)", (thread, i))?;
+                    }
+                    Ok(())
+                }))
+            })
+            .collect::<Result<Vec<_>, QuackError>>()?;
+        // Readers connect to the shared database themselves
+        std::thread::scope(|s| {
+            let readers: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| -> Result<(), QuackError> {
+                        let conn = db.connect()?;
+                        for _ in 0..20 {
+                            let count = conn
+                                .query_result("SELECT count(*) FROM t")?
+                                .get::<i64>(0, 0)?;
+                            assert!(count.is_some_and(|c| c <= 8 * 1050));
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            readers.into_iter().try_for_each(|r| r.join().unwrap())
+        })?;
+        for writer in writers {
+            writer.join().unwrap()?;
+        }
+        let result = conn.query_result("SELECT count(*), count(DISTINCT thread) FROM t")?;
+        assert_eq!(result.get::<i64>(0, 0)?, Some(8 * 1050));
+        assert_eq!(result.get::<i64>(1, 0)?, Some(8));
+        Ok(())
+    }
 }
@@ -0,0 +1,470 @@
+use crate::{
+    arrow::ArrowResult,
+    connection::{Connection, ConnectionError},
+    sql::{quote_identifier, quote_literal, quote_qualified},
+};
+
+/// File formats of `COPY`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Parquet,
+    Csv,
+    Json,
+}
+
+impl FileFormat {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Parquet => "PARQUET",
+            Self::Csv => "CSV",
+            Self::Json => "JSON",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Uncompressed,
+    /// Parquet only
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn name(self, format: Option<FileFormat>) -> &'static str {
+        match self {
+            Self::Uncompressed if format == Some(FileFormat::Parquet) => "uncompressed",
+            Self::Uncompressed => "none",
+            Self::Snappy => "snappy",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+/// Rows written by `COPY ... TO`
+#[derive(Debug, Clone)]
+pub enum CopySource {
+    Table(Vec<String>),
+    Query(String),
+}
+
+impl CopySource {
+    pub fn table(name: &str) -> Self {
+        Self::Table(vec![name.to_owned()])
+    }
+    pub fn table_in(schema: &str, name: &str) -> Self {
+        Self::Table(vec![schema.to_owned(), name.to_owned()])
+    }
+    pub fn query(sql: &str) -> Self {
+        Self::Query(sql.to_owned())
+    }
+    fn to_sql(&self) -> String {
+        match self {
+            Self::Table(parts) => quote_qualified(parts),
+            Self::Query(sql) => format!("({sql})"),
+        }
+    }
+}
+
+/// Options rendered as SQL, in the order they were set after the format and compression
+#[derive(Debug, Clone, Default)]
+struct Options {
+    format: Option<FileFormat>,
+    /// Rendered with the format, which decides its spelling
+    compression: Option<Compression>,
+    options: Vec<(&'static str, String)>,
+}
+
+impl Options {
+    fn set(&mut self, name: &'static str, value: String) {
+        self.options.retain(|(n, _)| *n != name);
+        self.options.push((name, value));
+    }
+    fn copy_options(&self) -> String {
+        let format = self
+            .format
+            .map(|f| ("FORMAT", f.name().to_owned()))
+            .into_iter();
+        let compression = self
+            .compression
+            .map(|c| ("COMPRESSION", quote_literal(c.name(self.format))))
+            .into_iter();
+        let options: Vec<_> = format
+            .chain(compression)
+            .chain(self.options.iter().map(|(n, v)| (*n, v.clone())))
+            .map(|(name, value)| format!("{name} {value}"))
+            .collect();
+        if options.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", options.join(", "))
+        }
+    }
+}
+
+fn identifier_list<S: AsRef<str>>(names: &[S]) -> String {
+    let names: Vec<_> = names.iter().map(|n| quote_identifier(n.as_ref())).collect();
+    format!("({})", names.join(", "))
+}
+
+fn bool_literal(value: bool) -> String {
+    value.to_string()
+}
+
+fn char_literal(value: char) -> String {
+    quote_literal(value.encode_utf8(&mut [0; 4]))
+}
+
+/// Run a `COPY` statement and read the number of rows it reports
+fn run_copy(connection: &Connection, sql: &str) -> Result<u64, ConnectionError> {
+    let result = connection.query_result(sql)?;
+    let count = result
+        .get::<i64>(0, 0)
+        .map_err(|e| ConnectionError::QueryError(e.to_string()))?;
+    Ok(count.unwrap_or_default() as u64)
+}
+
+/// Builder of `COPY ... TO`, see [`Connection::copy_to`]
+#[derive(Debug, Clone)]
+pub struct CopyTo<'a> {
+    connection: &'a Connection,
+    source: CopySource,
+    path: String,
+    options: Options,
+}
+
+impl<'a> CopyTo<'a> {
+    pub fn format(mut self, format: FileFormat) -> Self {
+        self.options.format = Some(format);
+        self
+    }
+    pub fn parquet(self) -> Self {
+        self.format(FileFormat::Parquet)
+    }
+    pub fn csv(self) -> Self {
+        self.format(FileFormat::Csv)
+    }
+    pub fn json(self) -> Self {
+        self.format(FileFormat::Json)
+    }
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.options.compression = Some(compression);
+        self
+    }
+    /// Parquet row group size in rows
+    pub fn row_group_size(mut self, rows: u64) -> Self {
+        self.options.set("ROW_GROUP_SIZE", rows.to_string());
+        self
+    }
+    /// Write one directory per distinct value of `columns` under the path
+    pub fn partition_by<S: AsRef<str>>(mut self, columns: &[S]) -> Self {
+        self.options.set("PARTITION_BY", identifier_list(columns));
+        self
+    }
+    /// Allow writing partitions into an existing directory
+    pub fn overwrite_or_ignore(mut self, enabled: bool) -> Self {
+        self.options
+            .set("OVERWRITE_OR_IGNORE", bool_literal(enabled));
+        self
+    }
+    /// CSV header line
+    pub fn header(mut self, enabled: bool) -> Self {
+        self.options.set("HEADER", bool_literal(enabled));
+        self
+    }
+    /// CSV delimiter
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.options.set("DELIMITER", char_literal(delimiter));
+        self
+    }
+    /// Any other option, with `value` already quoted as SQL
+    pub fn option(mut self, name: &'static str, value: &str) -> Self {
+        self.options.set(name, value.to_owned());
+        self
+    }
+    pub fn to_sql(&self) -> String {
+        format!(
+            "COPY {} TO {}{}",
+            self.source.to_sql(),
+            quote_literal(&self.path),
+            self.options.copy_options()
+        )
+    }
+    /// Run the copy and return the number of rows written
+    pub fn execute(&self) -> Result<u64, ConnectionError> {
+        run_copy(self.connection, &self.to_sql())
+    }
+}
+
+/// Builder of `COPY ... FROM`, see [`Connection::copy_from`]
+#[derive(Debug, Clone)]
+pub struct CopyFrom<'a> {
+    connection: &'a Connection,
+    table: Vec<String>,
+    path: String,
+    options: Options,
+}
+
+impl<'a> CopyFrom<'a> {
+    pub fn format(mut self, format: FileFormat) -> Self {
+        self.options.format = Some(format);
+        self
+    }
+    pub fn parquet(self) -> Self {
+        self.format(FileFormat::Parquet)
+    }
+    pub fn csv(self) -> Self {
+        self.format(FileFormat::Csv)
+    }
+    pub fn json(self) -> Self {
+        self.format(FileFormat::Json)
+    }
+    /// CSV header line
+    pub fn header(mut self, enabled: bool) -> Self {
+        self.options.set("HEADER", bool_literal(enabled));
+        self
+    }
+    /// CSV delimiter
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.options.set("DELIMITER", char_literal(delimiter));
+        self
+    }
+    /// CSV quote character
+    pub fn quote(mut self, quote: char) -> Self {
+        self.options.set("QUOTE", char_literal(quote));
+        self
+    }
+    /// CSV text read as `NULL`
+    pub fn null_str(mut self, null: &str) -> Self {
+        self.options.set("NULLSTR", quote_literal(null));
+        self
+    }
+    /// Any other option, with `value` already quoted as SQL
+    pub fn option(mut self, name: &'static str, value: &str) -> Self {
+        self.options.set(name, value.to_owned());
+        self
+    }
+    pub fn to_sql(&self) -> String {
+        format!(
+            "COPY {} FROM {}{}",
+            quote_qualified(&self.table),
+            quote_literal(&self.path),
+            self.options.copy_options()
+        )
+    }
+    /// Run the copy and return the number of rows loaded
+    pub fn execute(&self) -> Result<u64, ConnectionError> {
+        run_copy(self.connection, &self.to_sql())
+    }
+}
+
+/// Builder of a `read_csv` table function call, see [`Connection::read_csv`]
+#[derive(Debug, Clone)]
+pub struct ReadCsv<'a> {
+    connection: &'a Connection,
+    path: String,
+    options: Vec<(&'static str, String)>,
+}
+
+impl<'a> ReadCsv<'a> {
+    fn set(mut self, name: &'static str, value: String) -> Self {
+        self.options.retain(|(n, _)| *n != name);
+        self.options.push((name, value));
+        self
+    }
+    pub fn header(self, enabled: bool) -> Self {
+        self.set("header", bool_literal(enabled))
+    }
+    pub fn delimiter(self, delimiter: char) -> Self {
+        self.set("delim", char_literal(delimiter))
+    }
+    pub fn quote(self, quote: char) -> Self {
+        self.set("quote", char_literal(quote))
+    }
+    pub fn null_str(self, null: &str) -> Self {
+        self.set("nullstr", quote_literal(null))
+    }
+    pub fn skip(self, rows: u64) -> Self {
+        self.set("skip", rows.to_string())
+    }
+    pub fn compression(self, compression: Compression) -> Self {
+        self.set("compression", quote_literal(compression.name(None)))
+    }
+    pub fn date_format(self, format: &str) -> Self {
+        self.set("dateformat", quote_literal(format))
+    }
+    pub fn timestamp_format(self, format: &str) -> Self {
+        self.set("timestampformat", quote_literal(format))
+    }
+    /// Column names and SQL types, which turns off type detection
+    pub fn columns<N: AsRef<str>, T: AsRef<str>>(self, columns: &[(N, T)]) -> Self {
+        let columns: Vec<_> = columns
+            .iter()
+            .map(|(name, ty)| {
+                format!(
+                    "{}: {}",
+                    quote_literal(name.as_ref()),
+                    quote_literal(ty.as_ref())
+                )
+            })
+            .collect();
+        self.set("columns", format!("{{{}}}", columns.join(", ")))
+    }
+    /// Any other option, with `value` already quoted as SQL
+    pub fn option(self, name: &'static str, value: &str) -> Self {
+        self.set(name, value.to_owned())
+    }
+    /// The table function call, usable as a table in a query
+    pub fn to_sql(&self) -> String {
+        let mut args = vec![quote_literal(&self.path)];
+        args.extend(self.options.iter().map(|(n, v)| format!("{n} = {v}")));
+        format!("read_csv({})", args.join(", "))
+    }
+    pub fn query(&self) -> Result<ArrowResult, ConnectionError> {
+        self.connection
+            .query(&format!("SELECT * FROM {}", self.to_sql()))
+    }
+    /// Insert all rows into an existing table and return the number of rows inserted
+    pub fn insert_into(&self, schema: Option<&str>, table: &str) -> Result<u64, ConnectionError> {
+        let table = match schema {
+            Some(schema) => quote_qualified(&[schema, table]),
+            None => quote_identifier(table),
+        };
+        let sql = format!("INSERT INTO {table} SELECT * FROM {}", self.to_sql());
+        Ok(self.connection.query_result(&sql)?.rows_changed())
+    }
+}
+
+impl Connection {
+    /// Export a table or query result to a file or directory
+    pub fn copy_to(&self, source: CopySource, path: &str) -> CopyTo<'_> {
+        CopyTo {
+            connection: self,
+            source,
+            path: path.to_owned(),
+            options: Options::default(),
+        }
+    }
+    /// Load a file into an existing table
+    pub fn copy_from(&self, schema: Option<&str>, table: &str, path: &str) -> CopyFrom<'_> {
+        let table = schema
+            .into_iter()
+            .chain([table])
+            .map(str::to_owned)
+            .collect();
+        CopyFrom {
+            connection: self,
+            table,
+            path: path.to_owned(),
+            options: Options::default(),
+        }
+    }
+    /// Read a CSV file, or files matching a glob pattern
+    pub fn read_csv(&self, path: &str) -> ReadCsv<'_> {
+        ReadCsv {
+            connection: self,
+            path: path.to_owned(),
+            options: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{array::AsArray, datatypes::Int32Type, error::ArrowError};
+
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_copy_sql() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        let sql = conn
+            .copy_to(CopySource::table_in("main", "it's"), "out'dir")
+            .parquet()
+            .compression(Compression::Zstd)
+            .row_group_size(1000)
+            .partition_by(&["year", "mo\"nth"])
+            .to_sql();
+        assert_eq!(
+            sql,
+            "COPY \"main\".\"it's\" TO 'out''dir' (FORMAT PARQUET, COMPRESSION 'zstd', \
+             ROW_GROUP_SIZE 1000, PARTITION_BY (\"year\", \"mo\"\"nth\"))"
+        );
+        // the spelling of the compression follows the format, whenever it is set
+        let sql = conn
+            .copy_to(CopySource::table("t"), "out.parquet")
+            .compression(Compression::Uncompressed)
+            .parquet()
+            .to_sql();
+        assert_eq!(
+            sql,
+            "COPY \"t\" TO 'out.parquet' (FORMAT PARQUET, COMPRESSION 'uncompressed')"
+        );
+        let sql = conn
+            .read_csv("a.csv")
+            .header(true)
+            .delimiter(';')
+            .columns(&[("id", "INTEGER")])
+            .to_sql();
+        assert_eq!(
+            sql,
+            "read_csv('a.csv', header = true, delim = ';', columns = {'id': 'INTEGER'})"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_csv() -> Result<(), QuackError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("it's.csv");
+        let path = path.to_str().unwrap();
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER, name VARCHAR)")?;
+        conn.query("INSERT INTO t SELECT range, 'n' || range FROM range(10)")?;
+
+        let written = conn
+            .copy_to(CopySource::query("SELECT * FROM t WHERE id < 5"), path)
+            .csv()
+            .header(true)
+            .delimiter('|')
+            .execute()?;
+        assert_eq!(written, 5);
+
+        conn.query("CREATE TABLE u(id INTEGER, name VARCHAR)")?;
+        let loaded = conn
+            .copy_from(None, "u", path)
+            .csv()
+            .header(true)
+            .delimiter('|')
+            .execute()?;
+        assert_eq!(loaded, 5);
+        let inserted = conn
+            .read_csv(path)
+            .header(true)
+            .delimiter('|')
+            .insert_into(Some("main"), "u")?;
+        assert_eq!(inserted, 5);
+
+        let batches = conn
+            .read_csv(path)
+            .header(true)
+            .delimiter('|')
+            .columns(&[("id", "INTEGER"), ("name", "VARCHAR")])
+            .query()?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        let ids: Vec<_> = batches[0]
+            .column(0)
+            .as_primitive::<Int32Type>()
+            .values()
+            .to_vec();
+        assert_eq!(ids, [0, 1, 2, 3, 4]);
+        Ok(())
+    }
+}
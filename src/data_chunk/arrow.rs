@@ -0,0 +1,145 @@
+use arrow::{
+    array::{Array, ArrowPrimitiveType, AsArray, Decimal128Array},
+    datatypes::*,
+    error::ArrowError,
+};
+use quackdb_internal::ffi;
+
+use super::Vector;
+
+impl Vector {
+    /// Copy an Arrow array into the vector, starting from the first row.
+    ///
+    /// # Safety
+    /// * The vector type must be [`LogicalType::from_arrow`](crate::types::LogicalType::from_arrow)
+    ///   of the array type
+    /// * The array must not be longer than [`vector_size()`](super::vector_size)
+    pub unsafe fn write_arrow(&self, array: &dyn Array) -> Result<(), ArrowError> {
+        match array.data_type() {
+            DataType::Boolean => {
+                let data: *mut bool = self.data().cast();
+                for (i, v) in array.as_boolean().values().iter().enumerate() {
+                    data.add(i).write(v);
+                }
+            }
+            DataType::Int8 => self.write_primitive::<Int8Type>(array),
+            DataType::Int16 => self.write_primitive::<Int16Type>(array),
+            DataType::Int32 => self.write_primitive::<Int32Type>(array),
+            DataType::Int64 => self.write_primitive::<Int64Type>(array),
+            DataType::UInt8 => self.write_primitive::<UInt8Type>(array),
+            DataType::UInt16 => self.write_primitive::<UInt16Type>(array),
+            DataType::UInt32 => self.write_primitive::<UInt32Type>(array),
+            DataType::UInt64 => self.write_primitive::<UInt64Type>(array),
+            DataType::Float32 => self.write_primitive::<Float32Type>(array),
+            DataType::Float64 => self.write_primitive::<Float64Type>(array),
+            DataType::Date32 => self.write_primitive::<Date32Type>(array),
+            DataType::Time64(TimeUnit::Microsecond) => {
+                self.write_primitive::<Time64MicrosecondType>(array)
+            }
+            DataType::Timestamp(TimeUnit::Second, _) => {
+                self.write_primitive::<TimestampSecondType>(array)
+            }
+            DataType::Timestamp(TimeUnit::Millisecond, _) => {
+                self.write_primitive::<TimestampMillisecondType>(array)
+            }
+            DataType::Timestamp(TimeUnit::Microsecond, _) => {
+                self.write_primitive::<TimestampMicrosecondType>(array)
+            }
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+                self.write_primitive::<TimestampNanosecondType>(array)
+            }
+            DataType::Interval(IntervalUnit::MonthDayNano) => {
+                let data: *mut ffi::duckdb_interval = self.data().cast();
+                let array = array.as_primitive::<IntervalMonthDayNanoType>();
+                for (i, &v) in array.values().iter().enumerate() {
+                    let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(v);
+                    data.add(i).write(ffi::duckdb_interval {
+                        months,
+                        days,
+                        micros: nanos / 1000,
+                    });
+                }
+            }
+            &DataType::Decimal128(width, _) => {
+                // DuckDB picks the physical decimal type by width
+                let array = array.as_primitive::<Decimal128Type>();
+                match width {
+                    0..=4 => self.write_decimal::<i16>(array)?,
+                    5..=9 => self.write_decimal::<i32>(array)?,
+                    10..=18 => self.write_decimal::<i64>(array)?,
+                    _ => self.write_decimal::<i128>(array)?,
+                }
+            }
+            DataType::Utf8 => {
+                for (i, v) in array.as_string::<i32>().iter().enumerate() {
+                    if let Some(v) = v {
+                        self.assign_string_element(i as u64, v.as_bytes());
+                    }
+                }
+            }
+            DataType::LargeUtf8 => {
+                for (i, v) in array.as_string::<i64>().iter().enumerate() {
+                    if let Some(v) = v {
+                        self.assign_string_element(i as u64, v.as_bytes());
+                    }
+                }
+            }
+            DataType::Binary => {
+                for (i, v) in array.as_binary::<i32>().iter().enumerate() {
+                    if let Some(v) = v {
+                        self.assign_string_element(i as u64, v);
+                    }
+                }
+            }
+            DataType::LargeBinary => {
+                for (i, v) in array.as_binary::<i64>().iter().enumerate() {
+                    if let Some(v) = v {
+                        self.assign_string_element(i as u64, v);
+                    }
+                }
+            }
+            other => {
+                return Err(ArrowError::NotYetImplemented(format!(
+                    "copying {other} into a duckdb vector"
+                )))
+            }
+        }
+        if let Some(nulls) = array.nulls() {
+            for i in 0..array.len() {
+                if nulls.is_null(i) {
+                    self.set_null(i as u64);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    unsafe fn write_primitive<T: ArrowPrimitiveType>(&self, array: &dyn Array) {
+        let values = array.as_primitive::<T>().values();
+        let data: *mut T::Native = self.data().cast();
+        std::ptr::copy_nonoverlapping(values.as_ptr(), data, values.len());
+    }
+
+    /// Null slots are written as zero, while valid values out of range of `T` are errors
+    unsafe fn write_decimal<T: TryFrom<i128> + Default>(
+        &self,
+        array: &Decimal128Array,
+    ) -> Result<(), ArrowError> {
+        let data: *mut T = self.data().cast();
+        for (i, &v) in array.values().iter().enumerate() {
+            let value = match T::try_from(v) {
+                Ok(value) => value,
+                Err(_) if array.is_null(i) => T::default(),
+                Err(_) => {
+                    return Err(ArrowError::InvalidArgumentError(format!(
+                        "decimal value {v} does not fit in {} bytes",
+                        std::mem::size_of::<T>()
+                    )))
+                }
+            };
+            // `hugeint` is only 8-byte aligned
+            data.add(i).write_unaligned(value);
+        }
+        Ok(())
+    }
+}
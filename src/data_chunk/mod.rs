@@ -0,0 +1,80 @@
+mod arrow;
+mod row;
+pub use row::*;
+mod vector;
+pub use vector::*;
+
+use std::ops::Deref;
+
+use quackdb_internal::{ffi, handles::DataChunkHandle};
+
+/// Number of rows a data chunk holds at most
+pub fn vector_size() -> u64 {
+    unsafe { ffi::duckdb_vector_size() }
+}
+
+/// A data chunk owned by DuckDB, e.g. the output chunk of a table function
+#[derive(Debug)]
+pub struct DataChunk {
+    handle: ffi::duckdb_data_chunk,
+}
+
+impl From<ffi::duckdb_data_chunk> for DataChunk {
+    fn from(handle: ffi::duckdb_data_chunk) -> Self {
+        Self { handle }
+    }
+}
+
+impl DataChunk {
+    pub fn column_count(&self) -> u64 {
+        unsafe { ffi::duckdb_data_chunk_get_column_count(**self) }
+    }
+    /// Number of rows in the chunk
+    pub fn size(&self) -> u64 {
+        unsafe { ffi::duckdb_data_chunk_get_size(**self) }
+    }
+    /// Set the number of rows in the chunk. Must not exceed [`vector_size()`].
+    pub fn set_size(&self, size: u64) {
+        unsafe { ffi::duckdb_data_chunk_set_size(**self, size) }
+    }
+    pub fn reset(&self) {
+        unsafe { ffi::duckdb_data_chunk_reset(**self) }
+    }
+    /// Vector of a column, or `None` if out of range
+    pub fn vector(&self, column_index: u64) -> Option<Vector> {
+        if column_index >= self.column_count() {
+            return None;
+        }
+        Some(unsafe { ffi::duckdb_data_chunk_get_vector(**self, column_index) }.into())
+    }
+}
+
+impl Deref for DataChunk {
+    type Target = ffi::duckdb_data_chunk;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+/// A data chunk owned by Rust, e.g. fetched from a query result
+#[derive(Debug)]
+pub struct OwnedDataChunk {
+    pub handle: DataChunkHandle,
+    chunk: DataChunk,
+}
+
+impl From<DataChunkHandle> for OwnedDataChunk {
+    fn from(handle: DataChunkHandle) -> Self {
+        let chunk = DataChunk::from(*handle);
+        Self { handle, chunk }
+    }
+}
+
+impl Deref for OwnedDataChunk {
+    type Target = DataChunk;
+
+    fn deref(&self) -> &Self::Target {
+        &self.chunk
+    }
+}
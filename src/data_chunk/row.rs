@@ -0,0 +1,55 @@
+use quackdb_internal::conversion::VectorParam;
+
+use crate::types::LogicalType;
+
+use super::DataChunk;
+
+/// A row that can be written into a data chunk, one value per column.
+///
+/// Implemented for tuples of up to 16 [`VectorParam`] values.
+///
+/// # Safety
+/// `write_row` must write one value per column of `logical_types`.
+pub unsafe trait Row {
+    /// Logical types of the columns
+    fn logical_types() -> Vec<LogicalType>;
+    /// # Safety
+    /// * Columns of `chunk` must be of [`Row::logical_types`]
+    /// * `row` must be less than [`vector_size()`](super::vector_size)
+    unsafe fn write_row(self, chunk: &DataChunk, row: u64);
+}
+
+macro_rules! impl_row_for_tuple {
+    ($($idx:tt $ty:ident),+) => {
+        unsafe impl<$($ty: VectorParam),+> Row for ($($ty,)+) {
+            fn logical_types() -> Vec<LogicalType> {
+                vec![$(LogicalType::from($ty::logical_type())),+]
+            }
+            unsafe fn write_row(self, chunk: &DataChunk, row: u64) {
+                $(
+                    self.$idx.write_vector_unchecked(
+                        quackdb_internal::ffi::duckdb_data_chunk_get_vector(**chunk, $idx),
+                        row,
+                    );
+                )+
+            }
+        }
+    };
+}
+
+impl_row_for_tuple! {0 T0}
+impl_row_for_tuple! {0 T0, 1 T1}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14}
+impl_row_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14, 15 T15}
@@ -0,0 +1,108 @@
+use std::{ffi::c_void, ops::Deref};
+
+use quackdb_internal::{
+    conversion::{FromDuckDb, VectorParam},
+    ffi,
+    handles::LogicalTypeHandle,
+};
+
+use crate::types::LogicalType;
+
+/// A column of a data chunk
+#[derive(Debug)]
+pub struct Vector {
+    handle: ffi::duckdb_vector,
+}
+
+impl From<ffi::duckdb_vector> for Vector {
+    fn from(handle: ffi::duckdb_vector) -> Self {
+        Self { handle }
+    }
+}
+
+impl Vector {
+    pub fn logical_type(&self) -> LogicalType {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_vector_get_column_type(**self)) }.into()
+    }
+    /// Raw data of the vector, laid out according to its type
+    pub fn data(&self) -> *mut c_void {
+        unsafe { ffi::duckdb_vector_get_data(**self) }
+    }
+    /// Validity mask, or null if all rows are valid
+    pub fn validity(&self) -> *mut u64 {
+        unsafe { ffi::duckdb_vector_get_validity(**self) }
+    }
+    /// # Safety
+    /// * `row` must be in range
+    pub unsafe fn is_valid(&self, row: u64) -> bool {
+        let validity = self.validity();
+        validity.is_null() || ffi::duckdb_validity_row_is_valid(validity, row)
+    }
+    /// # Safety
+    /// * `row` must be in range
+    pub unsafe fn set_null(&self, row: u64) {
+        ffi::duckdb_vector_ensure_validity_writable(**self);
+        ffi::duckdb_validity_set_row_invalid(self.validity(), row);
+    }
+    /// # Safety
+    /// * `row` must be in range
+    /// * Vector must be of type `T::logical_type()`
+    pub unsafe fn write<T: VectorParam>(&self, row: u64, value: T) {
+        value.write_vector_unchecked(**self, row)
+    }
+    /// # Safety
+    /// * `row` must be in range
+    /// * Vector must be of `VARCHAR` or `BLOB` type
+    pub unsafe fn assign_string_element(&self, row: u64, value: &[u8]) {
+        ffi::duckdb_vector_assign_string_element_len(
+            **self,
+            row,
+            value.as_ptr().cast(),
+            value.len() as u64,
+        )
+    }
+    /// Read the value of a row, or `None` if it is null
+    ///
+    /// # Safety
+    /// * `row` must be in range
+    /// * Vector must be of type `T::DUCKDB_TYPE_ID`, with values stored as
+    ///   `T::DuckDbRepresentation`
+    pub unsafe fn read<T: FromDuckDb>(&self, row: u64) -> Option<T>
+    where
+        T::DuckDbRepresentation: Copy,
+    {
+        if !self.is_valid(row) {
+            return None;
+        }
+        let data: *const T::DuckDbRepresentation = self.data().cast();
+        Some(T::from_duckdb(data.add(row as usize).read_unaligned()))
+    }
+    /// Read the bytes of a `VARCHAR` or `BLOB` row, or `None` if it is null
+    ///
+    /// # Safety
+    /// * `row` must be in range
+    /// * Vector must be of `VARCHAR` or `BLOB` type
+    pub unsafe fn read_bytes(&self, row: u64) -> Option<&[u8]> {
+        if !self.is_valid(row) {
+            return None;
+        }
+        // `string_t` is a 4 byte length followed by the inlined bytes if they fit in 12 bytes,
+        // otherwise by a 4 byte prefix and a pointer
+        let string: *const u8 = self.data().cast::<u8>().add(row as usize * 16);
+        let len = string.cast::<u32>().read_unaligned() as usize;
+        let ptr = if len <= 12 {
+            string.add(4)
+        } else {
+            string.add(8).cast::<*const u8>().read_unaligned()
+        };
+        Some(std::slice::from_raw_parts(ptr, len))
+    }
+}
+
+impl Deref for Vector {
+    type Target = ffi::duckdb_vector;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     ops::Deref,
     path::{Path, PathBuf},
     ptr,
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 use quackdb_internal::{
@@ -15,12 +15,17 @@ use quackdb_internal::{
 use crate::{
     config::Config,
     connection::Connection,
-    replacement_scan::{ReplacementScanError, ReplacementScanInfo},
+    panic::catch_panic,
+    replacement_scan::{
+        ReplacementScan, ReplacementScanError, ReplacementScanHandle, ReplacementScanInfo,
+        ReplacementScanRegistry, ReplacementScanRouter,
+    },
 };
 
 #[derive(Debug)]
 pub struct Database {
     handle: Arc<DatabaseHandle>,
+    replacement_scans: OnceLock<Arc<ReplacementScanRegistry>>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -31,11 +36,18 @@ pub enum DatabaseError {
     OpenError(String),
     #[error("duckdb connect error")]
     ConnectError,
+    #[error("checkpoint failed: {0}")]
+    CheckpointError(String),
+    #[error("database stays open until {0} connections are dropped")]
+    StillInUse(usize),
 }
 
 impl From<Arc<DatabaseHandle>> for Database {
     fn from(value: Arc<DatabaseHandle>) -> Self {
-        Self { handle: value }
+        Self {
+            handle: value,
+            replacement_scans: OnceLock::new(),
+        }
     }
 }
 
@@ -57,7 +69,7 @@ impl Database {
                 Ok(cstr)
             })
             .transpose()?;
-        let path_ptr = c_path.map(|p| p.as_ptr()).unwrap_or(ptr::null());
+        let path_ptr = c_path.as_ref().map_or(ptr::null(), |p| p.as_ptr());
         let mut db: ffi::duckdb_database = ptr::null_mut();
         let mut err = ptr::null_mut();
         let config = config.map(|c| ***c).unwrap_or(ptr::null_mut());
@@ -68,9 +80,7 @@ impl Database {
             unsafe { ffi::duckdb_free(err as _) };
             return Err(DatabaseError::OpenError(err_str));
         }
-        Ok(Self {
-            handle: unsafe { DatabaseHandle::from_raw(db) },
-        })
+        Ok(unsafe { DatabaseHandle::from_raw(db) }.into())
     }
 
     pub fn connect(&self) -> Result<Connection, DatabaseError> {
@@ -82,6 +92,22 @@ impl Database {
         Ok(unsafe { ConnectionHandle::from_raw(handle, self.handle.clone()) }.into())
     }
 
+    /// Checkpoint and close the database.
+    ///
+    /// The database is only closed once every connection to it is dropped. Statements,
+    /// results and appenders keep their connection alive, even after the [`Connection`]
+    /// itself is dropped. If any connection is still alive, this returns
+    /// [`DatabaseError::StillInUse`] with the number of connections, after the checkpoint.
+    pub fn close(self) -> Result<(), DatabaseError> {
+        self.connect()?
+            .query("CHECKPOINT")
+            .map_err(|e| DatabaseError::CheckpointError(e.to_string()))?;
+        match Arc::strong_count(&self.handle) - 1 {
+            0 => Ok(()),
+            n => Err(DatabaseError::StillInUse(n)),
+        }
+    }
+
     pub fn add_replacement_scan<F, D, E>(&self, replacement: F, extra: D)
     where
         E: std::error::Error,
@@ -100,12 +126,15 @@ impl Database {
         {
             let data: *const ExtraData<F, D> = data.cast();
             let info: ReplacementScanInfo = info.into();
-            let table_name = unsafe { CStr::from_ptr(table_name) }
-                .to_string_lossy()
-                .into_owned();
-            let res = unsafe { ((*data).replacement)(&info, table_name, &(*data).extra) };
-            if let Err(e) = res {
-                let msg = CString::new(e.to_string());
+            let res = catch_panic(|| {
+                let table_name = unsafe { CStr::from_ptr(table_name) }
+                    .to_string_lossy()
+                    .into_owned();
+                unsafe { ((*data).replacement)(&info, table_name, &(*data).extra) }
+                    .map_err(|e| e.to_string())
+            });
+            if let Err(e) = res.and_then(|r| r) {
+                let msg = CString::new(e);
                 let cstr = msg.as_deref().unwrap_or(cstr!(
                     "replacement scan callback returns error string with Nul"
                 ));
@@ -113,7 +142,8 @@ impl Database {
             }
         }
         extern "C" fn drop_extra_data<F, D>(ptr: *mut c_void) {
-            unsafe { drop::<Box<ExtraData<F, D>>>(Box::from_raw(ptr.cast())) }
+            let _ =
+                catch_panic(|| unsafe { drop::<Box<ExtraData<F, D>>>(Box::from_raw(ptr.cast())) });
         }
         let extra_data = Box::new(ExtraData { replacement, extra });
         unsafe {
@@ -125,6 +155,39 @@ impl Database {
             );
         }
     }
+
+    /// Add a replacement scan dispatching table names through `router`
+    pub fn add_replacement_scan_router(&self, router: ReplacementScanRouter) {
+        self.add_replacement_scan(
+            |info, table_name, router: &ReplacementScanRouter| router.replace(info, &table_name),
+            router,
+        )
+    }
+
+    /// Register a handler in the replacement scan registry of this database.
+    /// Handlers with higher `priority` are tried first.
+    pub fn register_replacement_scan<S: ReplacementScan>(
+        &self,
+        name: &str,
+        priority: i32,
+        scan: S,
+    ) -> ReplacementScanHandle {
+        self.replacement_scans().register(name, priority, scan)
+    }
+
+    /// Replacement scan registry, installed on first use
+    pub fn replacement_scans(&self) -> &ReplacementScanRegistry {
+        self.replacement_scans.get_or_init(|| {
+            let registry = Arc::new(ReplacementScanRegistry::default());
+            self.add_replacement_scan(
+                |info, table_name, registry: &Arc<ReplacementScanRegistry>| {
+                    registry.replace(info, &table_name)
+                },
+                registry.clone(),
+            );
+            registry
+        })
+    }
 }
 
 impl Deref for Database {
@@ -146,6 +209,37 @@ mod test {
         assert!(db.is_ok());
     }
 
+    #[test]
+    fn test_close() -> Result<(), crate::error::QuackError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("close.db");
+        let db = Database::open(Some(&path))?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t AS SELECT * FROM range(10)")?;
+        let mut appender = conn.appender(None, "t")?;
+        appender.append_row((10i64,))?;
+        appender.close()?;
+        // a result shares the connection it was created from
+        let result = conn.query_result("SELECT 1")?;
+        drop(conn);
+        assert!(matches!(db.close(), Err(DatabaseError::StillInUse(1))));
+        drop(result);
+
+        let db = Database::open(Some(&path))?;
+        let conn = db.connect()?;
+        let count = conn.query_result("SELECT count(*) FROM t")?;
+        assert_eq!(count.get::<i64>(0, 0)?, Some(11));
+        conn.query("CREATE TABLE u(id INTEGER PRIMARY KEY)")?;
+        let mut appender = conn.appender(None, "u")?;
+        appender.append_rows([(1,), (1,)])?;
+        // the duplicate key is only found by the final flush
+        assert!(appender.close().is_err());
+        drop(count);
+        drop(conn);
+        db.close()?;
+        Ok(())
+    }
+
     #[test]
     fn test_open_failure() -> Result<(), DatabaseError> {
         let filename = "no_such_file.db";
@@ -165,4 +259,23 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_replacement_scan_panic() -> Result<(), crate::error::QuackError> {
+        let db = Database::open(None)?;
+        db.add_replacement_scan(
+            |_, table_name, &_| -> Result<(), ReplacementScanError<std::convert::Infallible>> {
+                panic!("no scan for {table_name}")
+            },
+            (),
+        );
+        let conn = db.connect()?;
+        match conn.query("SELECT * FROM missing_table") {
+            Err(crate::connection::ConnectionError::QueryError(e)) => {
+                assert!(e.contains("no scan for missing_table"), "{e}")
+            }
+            other => panic!("expected query error, got {other:?}"),
+        }
+        Ok(())
+    }
 }
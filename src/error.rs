@@ -2,8 +2,10 @@ use arrow::error::ArrowError;
 use thiserror::Error;
 
 use crate::{
-    appender::AppenderError, arrow::ArrowResultError, connection::ConnectionError,
-    database::DatabaseError,
+    appender::AppenderError, arrow::ArrowResultError, catalog::CatalogError,
+    connection::ConnectionError, database::DatabaseError, extension::ExtensionError,
+    plan::PlanError, profile::ProfileError, query_result::QueryResultError,
+    statement::PreparedStatementError,
 };
 
 /// Convenience error type encompassing all sub-errors
@@ -14,9 +16,21 @@ pub enum QuackError {
     #[error(transparent)]
     Connection(#[from] ConnectionError),
     #[error(transparent)]
+    PreparedStatement(#[from] PreparedStatementError),
+    #[error(transparent)]
     Appender(#[from] AppenderError),
     #[error(transparent)]
     ArrowResult(#[from] ArrowResultError),
     #[error(transparent)]
+    QueryResult(#[from] QueryResultError),
+    #[error(transparent)]
     Arrow(#[from] ArrowError),
+    #[error(transparent)]
+    Catalog(#[from] CatalogError),
+    #[error(transparent)]
+    Extension(#[from] ExtensionError),
+    #[error(transparent)]
+    Profile(#[from] ProfileError),
+    #[error(transparent)]
+    Plan(#[from] PlanError),
 }
@@ -0,0 +1,134 @@
+use arrow::{error::ArrowError, record_batch::RecordBatch};
+use thiserror::Error;
+
+use crate::{
+    arrow::{booleans, lists, optional, string_list, strings, ArrowResultError},
+    connection::{Connection, ConnectionError},
+    sql::quote_literal,
+};
+
+#[derive(Error, Debug)]
+pub enum ExtensionError {
+    #[error("cannot install extension {extension}: {message}")]
+    InstallError { extension: String, message: String },
+    #[error("cannot load extension {extension}: {message}")]
+    LoadError { extension: String, message: String },
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+    #[error(transparent)]
+    ArrowResult(#[from] ArrowResultError),
+    #[error(transparent)]
+    Arrow(#[from] ArrowError),
+}
+
+/// A row of `duckdb_extensions()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub loaded: bool,
+    pub installed: bool,
+    pub install_path: Option<String>,
+    pub description: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+impl Connection {
+    /// Install an extension by name, or from a local extension file.
+    ///
+    /// Installing from a file needs no network access, which suits air-gapped hosts.
+    pub fn install_extension(&self, name_or_path: &str) -> Result<(), ExtensionError> {
+        self.query(&format!("INSTALL {}", quote_literal(name_or_path)))
+            .map_err(|e| ExtensionError::InstallError {
+                extension: name_or_path.to_owned(),
+                message: e.to_string(),
+            })?;
+        Ok(())
+    }
+    /// Load an installed extension by name, or an extension file by path.
+    ///
+    /// Files not signed by DuckDB need [`Config::allow_unsigned_extensions`].
+    ///
+    /// [`Config::allow_unsigned_extensions`]: crate::config::Config::allow_unsigned_extensions
+    pub fn load_extension(&self, name_or_path: &str) -> Result<(), ExtensionError> {
+        self.query(&format!("LOAD {}", quote_literal(name_or_path)))
+            .map_err(|e| ExtensionError::LoadError {
+                extension: name_or_path.to_owned(),
+                message: e.to_string(),
+            })?;
+        Ok(())
+    }
+    /// All extensions known to the database, loaded or not
+    pub fn extensions(&self) -> Result<Vec<ExtensionInfo>, ExtensionError> {
+        let batches = self
+            .query(
+                "SELECT extension_name, loaded, installed, install_path, description, aliases \
+                 FROM duckdb_extensions() ORDER BY extension_name",
+            )?
+            .into_stream()?
+            .collect::<Result<Vec<RecordBatch>, ArrowError>>()?;
+        let mut extensions = Vec::new();
+        for batch in &batches {
+            let (name, loaded, installed) =
+                (strings(batch, 0), booleans(batch, 1), booleans(batch, 2));
+            let (install_path, description, aliases) =
+                (strings(batch, 3), strings(batch, 4), lists(batch, 5));
+            for row in 0..batch.num_rows() {
+                extensions.push(ExtensionInfo {
+                    name: name.value(row).to_owned(),
+                    loaded: loaded.value(row),
+                    installed: installed.value(row),
+                    install_path: optional(install_path, row).filter(|p| !p.is_empty()),
+                    description: optional(description, row),
+                    aliases: string_list(aliases, row),
+                });
+            }
+        }
+        Ok(extensions)
+    }
+    /// Extensions currently loaded, including those built in
+    pub fn loaded_extensions(&self) -> Result<Vec<ExtensionInfo>, ExtensionError> {
+        let mut extensions = self.extensions()?;
+        extensions.retain(|e| e.loaded);
+        Ok(extensions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{config::Config, database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_extensions() -> Result<(), QuackError> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::new().unwrap();
+        config
+            .allow_unsigned_extensions(true)
+            .unwrap()
+            .extension_directory(dir.path().to_str().unwrap())
+            .unwrap();
+        let db = Database::open_ext(None, Some(&config))?;
+        let conn = db.connect()?;
+        let allowed = conn
+            .query_result("SELECT current_setting('allow_unsigned_extensions')")?
+            .get::<bool>(0, 0)?;
+        assert_eq!(allowed, Some(true));
+
+        let extensions = conn.extensions()?;
+        assert!(!extensions.is_empty());
+        assert!(conn.loaded_extensions()?.iter().all(|e| e.loaded));
+
+        let missing = dir.path().join("missing.duckdb_extension");
+        let missing = missing.to_str().unwrap();
+        assert!(matches!(
+            conn.load_extension(missing),
+            Err(ExtensionError::LoadError { extension, .. }) if extension == missing
+        ));
+        assert!(matches!(
+            conn.install_extension(missing),
+            Err(ExtensionError::InstallError { .. })
+        ));
+        Ok(())
+    }
+}
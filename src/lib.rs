@@ -1,13 +1,25 @@
 pub mod appender;
 pub mod arrow;
+pub mod catalog;
 pub mod config;
 pub mod connection;
+pub mod copy;
+pub mod data_chunk;
 pub mod database;
 pub mod error;
+pub mod extension;
+mod panic;
+pub mod plan;
+pub mod profile;
+pub mod query_result;
 pub mod replacement_scan;
+pub mod sql;
 pub mod statement;
 pub mod table_function;
+mod trace;
+mod tree;
 pub mod types;
+pub mod value;
 
 pub fn library_version() -> String {
     quackdb_internal::library_version()
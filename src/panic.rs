@@ -0,0 +1,34 @@
+//! Helpers for callbacks invoked from DuckDB.
+//!
+//! Unwinding across an `extern "C"` boundary is undefined behavior, so every callback
+//! handed to DuckDB runs its body through [`catch_panic`] and reports the panic as an
+//! ordinary error instead.
+
+use std::{
+    any::Any,
+    ffi::CString,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+/// Run `f`, converting a panic into its message.
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(panic_message)
+}
+
+/// Extract a readable message from a panic payload
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let msg = if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_owned()
+    };
+    format!("panic in callback: {msg}")
+}
+
+/// Convert an error message into a C string, escaping interior nul characters
+pub(crate) fn error_cstring(msg: impl Into<String>) -> CString {
+    let msg: String = msg.into();
+    CString::new(msg.replace('\0', r"\0")).expect("nul characters are escaped")
+}
@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::{
+    connection::{Connection, ConnectionError},
+    query_result::{QueryResult, QueryResultError},
+    sql::{quote_literal, replace_parameters},
+    statement::{Params, PreparedStatement, PreparedStatementError},
+    tree::depth_first,
+};
+
+/// Width of one operator box in DuckDB's plan rendering, including borders. Boxes of wide
+/// plans are narrowed two characters at a time down to [`MIN_NODE_WIDTH`] to fit in
+/// [`MAX_RENDER_WIDTH`], and columns that still do not fit are left out.
+const NODE_WIDTH: usize = 29;
+const MIN_NODE_WIDTH: usize = 15;
+const MAX_RENDER_WIDTH: usize = 240;
+
+#[derive(Error, Debug)]
+pub enum PlanError {
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+    #[error(transparent)]
+    PreparedStatement(#[from] PreparedStatementError),
+    #[error(transparent)]
+    QueryResult(#[from] QueryResultError),
+    #[error(
+        "operator boxes are {found} characters wide instead of {} to {}",
+        MIN_NODE_WIDTH,
+        NODE_WIDTH
+    )]
+    NodeWidth { found: usize },
+    #[error("plan is too wide for DuckDB to render all of its operators")]
+    Truncated,
+}
+
+/// Physical plan of a query, parsed from `EXPLAIN` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    pub root: Option<PlanNode>,
+    /// The rendered plan as returned by DuckDB
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanNode {
+    /// Operator name, such as `SEQ_SCAN` or `HASH_JOIN`
+    pub name: String,
+    /// Operator details in the sections DuckDB separates them into, e.g. the scanned
+    /// table, projected columns and pushed down filters of a scan
+    pub details: Vec<Vec<String>>,
+    pub estimated_cardinality: Option<u64>,
+    pub children: Vec<PlanNode>,
+}
+
+impl QueryPlan {
+    /// Parse the rendered physical plan of `EXPLAIN`.
+    ///
+    /// Fails if the operator boxes are not as wide as in the renderer of DuckDB 0.10, or
+    /// if the plan fills the rendering width, as DuckDB then may have left operators out.
+    pub fn parse(text: &str) -> Result<Self, PlanError> {
+        let lines: Vec<Vec<char>> = text.lines().map(|l| l.chars().collect()).collect();
+        let width = node_width(&lines)?;
+        let rows = parse_rows(&lines, width);
+        if width == MIN_NODE_WIDTH
+            && rows
+                .iter()
+                .flatten()
+                .any(|node| (node.x + 1) * width >= MAX_RENDER_WIDTH)
+        {
+            return Err(PlanError::Truncated);
+        }
+        let root = rows
+            .first()
+            .and_then(|row| row.first())
+            .map(|_| build_node(&rows, 0, 0));
+        Ok(Self {
+            root,
+            text: text.to_owned(),
+        })
+    }
+    /// Every node of the plan, parents before their children
+    pub fn nodes(&self) -> Vec<&PlanNode> {
+        self.root.iter().flat_map(|root| root.nodes()).collect()
+    }
+    /// Table scans reading every row: no filter is pushed into the scan and no `FILTER`
+    /// operator sits directly above it
+    pub fn full_table_scans(&self) -> Vec<&PlanNode> {
+        let mut scans = Vec::new();
+        if let Some(root) = &self.root {
+            collect_full_scans(root, false, &mut scans);
+        }
+        scans
+    }
+    /// Whether every scan of `table` is filtered, either by pushed down filters or by a
+    /// `FILTER` operator directly above it. `None` if the table is not scanned.
+    pub fn is_filtered(&self, table: &str) -> Option<bool> {
+        let scans: Vec<_> = self
+            .nodes()
+            .into_iter()
+            .filter(|n| n.table() == Some(table))
+            .collect();
+        if scans.is_empty() {
+            return None;
+        }
+        let full_scans = self.full_table_scans();
+        Some(
+            scans
+                .iter()
+                .all(|scan| !full_scans.iter().any(|full| std::ptr::eq(*scan, *full))),
+        )
+    }
+}
+
+impl PlanNode {
+    /// This node and the nodes below it, parents before their children
+    pub fn nodes(&self) -> Vec<&PlanNode> {
+        depth_first(self, |node| node.children.as_slice())
+    }
+    pub fn is_table_scan(&self) -> bool {
+        matches!(self.name.as_str(), "SEQ_SCAN" | "INDEX_SCAN")
+    }
+    /// Name of the scanned table, for table scans
+    pub fn table(&self) -> Option<&str> {
+        if !self.is_table_scan() {
+            return None;
+        }
+        self.details.first()?.first().map(String::as_str)
+    }
+    /// Filter expressions of a `FILTER` operator, or filters pushed down into a scan
+    pub fn filters(&self) -> Vec<&str> {
+        if self.name == "FILTER" {
+            return self.details.iter().flatten().map(String::as_str).collect();
+        }
+        self.details
+            .iter()
+            .filter(|section| section.first().is_some_and(|l| l.starts_with("Filters:")))
+            .flatten()
+            .map(|line| line.trim_start_matches("Filters:").trim())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
+
+fn collect_full_scans<'a>(node: &'a PlanNode, under_filter: bool, scans: &mut Vec<&'a PlanNode>) {
+    if node.is_table_scan() && !under_filter && node.filters().is_empty() {
+        scans.push(node);
+    }
+    for child in &node.children {
+        collect_full_scans(child, node.name == "FILTER", scans);
+    }
+}
+
+/// An operator box: its grid column and its text lines
+struct RawNode {
+    x: usize,
+    lines: Vec<String>,
+}
+
+/// Width of the operator boxes, measured on the first box from the start of its line as
+/// boxes are located by their position in the line
+fn node_width(lines: &[Vec<char>]) -> Result<usize, PlanError> {
+    let Some(line) = lines.iter().find(|l| l.contains(&'┌')) else {
+        return Ok(NODE_WIDTH);
+    };
+    let found = line
+        .iter()
+        .position(|&c| c == '┐')
+        .map_or(line.len(), |right| right + 1);
+    let rendered = (MIN_NODE_WIDTH..=NODE_WIDTH).step_by(2).any(|w| w == found);
+    if line.first() != Some(&'┌') || !rendered {
+        return Err(PlanError::NodeWidth { found });
+    }
+    Ok(found)
+}
+
+fn cell(line: &[char], x: usize, width: usize) -> Option<&[char]> {
+    line.get(x * width..(x + 1) * width)
+}
+
+fn box_edges(line: &[char], width: usize, left: char, right: char) -> Vec<usize> {
+    (0..line.len() / width)
+        .filter(|&x| cell(line, x, width).is_some_and(|c| c[0] == left && c[width - 1] == right))
+        .collect()
+}
+
+/// Split the rendering into rows of operator boxes
+fn parse_rows(lines: &[Vec<char>], width: usize) -> Vec<Vec<RawNode>> {
+    let mut rows = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let tops = box_edges(&lines[i], width, '┌', '┐');
+        i += 1;
+        if tops.is_empty() {
+            continue;
+        }
+        let mut row: Vec<_> = tops
+            .into_iter()
+            .map(|x| RawNode {
+                x,
+                lines: Vec::new(),
+            })
+            .collect();
+        while i < lines.len() {
+            let line = &lines[i];
+            i += 1;
+            if !box_edges(line, width, '└', '┘').is_empty() {
+                break;
+            }
+            for node in &mut row {
+                if let Some(c) = cell(line, node.x, width) {
+                    let text: String = c[1..width - 1].iter().collect();
+                    node.lines.push(text.trim().to_owned());
+                }
+            }
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+fn is_separator(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| c == '─' || c == ' ')
+}
+
+fn parse_cardinality(line: &str) -> Option<u64> {
+    if let Some(ec) = line.strip_prefix("EC:") {
+        return ec.trim().parse().ok();
+    }
+    line.strip_prefix('~')?
+        .strip_suffix("Rows")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn build_node(rows: &[Vec<RawNode>], y: usize, index: usize) -> PlanNode {
+    let raw = &rows[y][index];
+    let mut lines = raw.lines.iter().filter(|l| !l.is_empty());
+    let name = lines.next().cloned().unwrap_or_default();
+    let mut details = Vec::new();
+    let mut section = Vec::new();
+    let mut estimated_cardinality = None;
+    for line in lines {
+        if is_separator(line) {
+            if !section.is_empty() {
+                details.push(std::mem::take(&mut section));
+            }
+        } else if let Some(ec) = parse_cardinality(line) {
+            estimated_cardinality = Some(ec);
+        } else {
+            section.push(line.clone());
+        }
+    }
+    if !section.is_empty() {
+        details.push(section);
+    }
+    // Children are drawn below, between this box and the next box of the same row
+    let end = rows[y].get(index + 1).map_or(usize::MAX, |next| next.x);
+    let children = rows
+        .get(y + 1)
+        .map(|below| {
+            (0..below.len())
+                .filter(|&i| (raw.x..end).contains(&below[i].x))
+                .map(|i| build_node(rows, y + 1, i))
+                .collect()
+        })
+        .unwrap_or_default();
+    PlanNode {
+        name,
+        details,
+        estimated_cardinality,
+        children,
+    }
+}
+
+impl Connection {
+    /// Plan a query without running it. Queries with parameters need
+    /// [`explain_with`](Self::explain_with).
+    pub fn explain(&self, sql: &str) -> Result<QueryPlan, PlanError> {
+        self.explain_with(sql, ())
+    }
+
+    /// Plan a query for the given parameter values without running it
+    pub fn explain_with<P: Params>(&self, sql: &str, params: P) -> Result<QueryPlan, PlanError> {
+        let mut statement = self.prepare(sql)?;
+        params.bind_to(&mut statement)?;
+        statement.explain()
+    }
+}
+
+impl PreparedStatement {
+    /// Plan the statement for its bound values on its connection, without running it.
+    ///
+    /// DuckDB cannot run `EXPLAIN` with bound parameters, so the values are written into
+    /// the SQL as literals instead.
+    pub fn explain(&self) -> Result<QueryPlan, PlanError> {
+        let connection = Connection::from(self.handle.connection().clone());
+        let mut query = self.handle.query().to_owned();
+        if self.nparams() > 0 {
+            let literals = self.parameter_literals(&connection)?;
+            query = replace_parameters(&query, |name| literals.get(name).cloned());
+        }
+        explain_result(&connection.query_result(&format!("EXPLAIN {query}"))?)
+    }
+
+    /// Bound values as typed literals, by parameter name
+    fn parameter_literals(
+        &self,
+        connection: &Connection,
+    ) -> Result<HashMap<String, String>, PlanError> {
+        // Selected through a subquery, so the values keep their own types
+        let n = self.nparams();
+        let columns = (1..=n)
+            .map(|i| format!("typeof(p{i}), p{i}::VARCHAR"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let values = (1..=n)
+            .map(|i| format!("${i} AS p{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut statement =
+            connection.prepare(&format!("SELECT {columns} FROM (SELECT {values})"))?;
+        statement.copy_bindings_from(self)?;
+        let result = statement.execute_result()?;
+        (1..=n)
+            .map(|i| {
+                let column = 2 * (i - 1);
+                let literal = match (
+                    result.get_string(column, 0)?,
+                    result.get_string(column + 1, 0)?,
+                ) {
+                    (Some(type_name), Some(value)) => {
+                        format!("CAST({} AS {type_name})", quote_literal(&value))
+                    }
+                    _ => "NULL".to_owned(),
+                };
+                let name = self.parameter_name(i).unwrap_or_else(|| i.to_string());
+                Ok((name, literal))
+            })
+            .collect()
+    }
+}
+
+/// Parse the physical plan in the result of `EXPLAIN`
+fn explain_result(result: &QueryResult) -> Result<QueryPlan, PlanError> {
+    let mut text = String::new();
+    for row in 0..result.row_count() {
+        if result.get_string(0, row)?.as_deref() == Some("physical_plan") {
+            text = result.get_string(1, row)?.unwrap_or_default();
+        }
+    }
+    QueryPlan::parse(&text)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_explain() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query(
+            "CREATE TABLE t AS SELECT range AS x, range % 7 AS y FROM range(1000); \
+             CREATE TABLE u AS SELECT range AS x FROM range(10);",
+        )?;
+
+        let plan = conn.explain("SELECT count(*) FROM t")?;
+        let scans = plan.full_table_scans();
+        assert_eq!(scans.len(), 1);
+        assert_eq!(scans[0].table(), Some("t"));
+        assert_eq!(scans[0].estimated_cardinality, Some(1000));
+        assert_eq!(plan.is_filtered("t"), Some(false));
+        assert_eq!(plan.is_filtered("u"), None);
+
+        let plan = conn.explain("SELECT * FROM t WHERE x = 5")?;
+        assert!(plan.full_table_scans().is_empty());
+        assert_eq!(plan.is_filtered("t"), Some(true));
+
+        let plan = conn.explain("SELECT * FROM t JOIN u USING (x)")?;
+        let join = plan
+            .nodes()
+            .into_iter()
+            .find(|n| n.name == "HASH_JOIN")
+            .unwrap();
+        let mut tables: Vec<_> = join.children.iter().filter_map(|c| c.table()).collect();
+        tables.sort();
+        assert_eq!(tables, ["t", "u"]);
+
+        // Parameters are planned with their values, not folded away as NULL
+        let sql = "SELECT * FROM t WHERE y = ?";
+        let plan = conn.explain_with(sql, (3,))?;
+        assert_eq!(plan.is_filtered("t"), Some(true));
+        assert!(plan.nodes().iter().all(|n| n.name != "EMPTY_RESULT"));
+        let mut statement = conn.prepare(sql)?;
+        statement.bind(3)?;
+        let plan = statement.explain()?;
+        assert_eq!(plan.is_filtered("t"), Some(true));
+        assert!(plan
+            .nodes()
+            .iter()
+            .flat_map(|n| n.filters())
+            .any(|f| f.contains('3')));
+        assert_eq!(statement.execute_result()?.row_count(), 143);
+        let mut named = conn.prepare("SELECT * FROM t WHERE y = $y AND x::VARCHAR <> $s")?;
+        named.bind_named("y", 3)?;
+        named.bind_named("s", "it's")?;
+        let plan = named.explain()?;
+        assert!(plan
+            .nodes()
+            .iter()
+            .flat_map(|n| n.filters())
+            .any(|f| f.contains("y=3")));
+        assert!(conn.explain(sql).is_err());
+        assert!(conn.explain("SELECT * FROM missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_wide_plan() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t AS SELECT range AS x FROM range(10)")?;
+        let union = |n| vec!["SELECT x FROM t"; n].join(" UNION ALL ");
+
+        // boxes are narrowed to fit ten scans side by side
+        let plan = conn.explain(&union(10))?;
+        assert_eq!(plan.full_table_scans().len(), 10);
+        assert!(matches!(
+            conn.explain(&union(20)),
+            Err(PlanError::Truncated)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_node_width() {
+        let text = "┌─────────┐\n│  PROJ   │\n└─────────┘";
+        assert!(matches!(
+            QueryPlan::parse(text),
+            Err(PlanError::NodeWidth { found: 11 })
+        ));
+        assert_eq!(QueryPlan::parse("").unwrap().root, None);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{fs::OpenOptions, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+use tempfile::TempDir;
+use thiserror::Error;
+
+use crate::{
+    arrow::ArrowResult,
+    connection::{Connection, ConnectionError},
+    query_result::QueryResultError,
+    sql::quote_literal,
+    tree::depth_first,
+};
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+    #[error(transparent)]
+    QueryResult(#[from] QueryResultError),
+    #[error("cannot read profile output: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("cannot parse profile output: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Output of DuckDB's JSON profiler for one query
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryProfile {
+    /// The profiled SQL text
+    pub query: String,
+    /// Total time spent on the query
+    pub total_time: Duration,
+    /// Time of optimizer and planner phases, only reported in detailed profiling mode
+    pub phases: Vec<PhaseTiming>,
+    /// Root of the physical operator tree
+    pub root: Option<OperatorProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PhaseTiming {
+    pub annotation: String,
+    #[serde(deserialize_with = "seconds")]
+    pub timing: Duration,
+}
+
+/// A physical operator and the operators feeding it
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OperatorProfile {
+    pub name: String,
+    #[serde(default, deserialize_with = "seconds")]
+    pub timing: Duration,
+    /// Rows produced by the operator
+    #[serde(default)]
+    pub cardinality: u64,
+    /// Operator details, such as projections, filters or the scanned table
+    #[serde(default)]
+    pub extra_info: String,
+    #[serde(default)]
+    pub children: Vec<OperatorProfile>,
+}
+
+#[derive(Deserialize)]
+struct RawProfile {
+    #[serde(default)]
+    timing: Option<f64>,
+    #[serde(default)]
+    result: Option<serde_json::Value>,
+    #[serde(default, rename = "extra-info", alias = "extra_info")]
+    query: String,
+    #[serde(default)]
+    timings: Vec<PhaseTiming>,
+    #[serde(default)]
+    children: Vec<OperatorProfile>,
+}
+
+fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let secs = f64::deserialize(deserializer)?;
+    Ok(Duration::try_from_secs_f64(secs).unwrap_or_default())
+}
+
+impl QueryProfile {
+    /// Parse the output of `enable_profiling = 'json'`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let raw: RawProfile = serde_json::from_str(json)?;
+        let total = raw
+            .timing
+            .or_else(|| raw.result.as_ref().and_then(serde_json::Value::as_f64))
+            .unwrap_or_default();
+        Ok(Self {
+            query: raw.query,
+            total_time: Duration::try_from_secs_f64(total).unwrap_or_default(),
+            phases: raw.timings,
+            root: raw.children.into_iter().next(),
+        })
+    }
+    /// All operators, depth first from the root
+    pub fn operators(&self) -> Vec<&OperatorProfile> {
+        self.root.iter().flat_map(|root| root.operators()).collect()
+    }
+}
+
+impl OperatorProfile {
+    /// This operator followed by all operators below it, depth first
+    pub fn operators(&self) -> Vec<&OperatorProfile> {
+        depth_first(self, |operator| operator.children.as_slice())
+    }
+}
+
+/// File for profiler output in a new directory only accessible to the current user, so
+/// concurrent profiles don't collide and other users can't plant or read the file. Both
+/// are removed when the directory is dropped.
+fn profile_file() -> Result<(TempDir, PathBuf), std::io::Error> {
+    let dir = tempfile::Builder::new()
+        .prefix("quackdb-profile")
+        .tempdir()?;
+    let path = dir.path().join("profile.json");
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok((dir, path))
+}
+
+impl Connection {
+    /// Run a query with the JSON profiler enabled and return its result and profile.
+    ///
+    /// The connection's `enable_profiling` and `profiling_output` settings are restored
+    /// afterwards, even if the query fails.
+    pub fn profile(&self, sql: &str) -> Result<(ArrowResult, QueryProfile), ProfileError> {
+        let settings = self.query_result(
+            "SELECT current_setting('enable_profiling')::VARCHAR, \
+             current_setting('profiling_output')::VARCHAR",
+        )?;
+        let (enabled, output) = (settings.get_string(0, 0)?, settings.get_string(1, 0)?);
+        let (_dir, path) = profile_file()?;
+        let result = self
+            .query(&format!(
+                "SET enable_profiling = 'json'; SET profiling_output = {}",
+                quote_literal(&path.to_string_lossy())
+            ))
+            .and_then(|_| self.query(sql));
+        let restored = self.restore_profiling(enabled.as_deref(), output.as_deref());
+        let json = std::fs::read_to_string(&path);
+        let result = result?;
+        restored?;
+        Ok((result, QueryProfile::from_json(&json?)?))
+    }
+
+    fn restore_profiling(
+        &self,
+        enabled: Option<&str>,
+        output: Option<&str>,
+    ) -> Result<(), ConnectionError> {
+        let enabled = match enabled {
+            Some(format) => format!("SET enable_profiling = {}", quote_literal(format)),
+            None => "RESET enable_profiling".to_owned(),
+        };
+        let output = match output {
+            Some(path) if !path.is_empty() => {
+                format!("SET profiling_output = {}", quote_literal(path))
+            }
+            _ => "RESET profiling_output".to_owned(),
+        };
+        self.query(&format!("{enabled}; {output}"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() -> Result<(), serde_json::Error> {
+        let json = r#"{
+            "name": "Query",
+            "result": 0.5,
+            "timing": 0.5,
+            "cardinality": 1,
+            "extra-info": "SELECT 1",
+            "timings": [{"annotation": "optimizer", "timing": 0.25}],
+            "children": [{
+                "name": "PROJECTION",
+                "timing": 0.125,
+                "cardinality": 1,
+                "extra_info": "1\n",
+                "timings": [],
+                "children": [{
+                    "name": "DUMMY_SCAN",
+                    "timing": 0.0,
+                    "cardinality": 1,
+                    "extra_info": "",
+                    "timings": [],
+                    "children": []
+                }]
+            }]
+        }"#;
+        let profile = QueryProfile::from_json(json)?;
+        assert_eq!(profile.query, "SELECT 1");
+        assert_eq!(profile.total_time, Duration::from_millis(500));
+        assert_eq!(profile.phases[0].annotation, "optimizer");
+        let names: Vec<_> = profile
+            .operators()
+            .iter()
+            .map(|o| o.name.as_str())
+            .collect();
+        assert_eq!(names, ["PROJECTION", "DUMMY_SCAN"]);
+        assert_eq!(profile.operators()[0].timing, Duration::from_millis(125));
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t AS SELECT range AS x FROM range(1000)")?;
+        let (result, profile) = conn.profile("SELECT count(*) FROM t WHERE x >= 10")?;
+        assert_eq!(result.row_count(), 1);
+        let scan = profile
+            .operators()
+            .into_iter()
+            .find(|o| o.name.contains("SCAN"))
+            .unwrap();
+        assert_eq!(scan.cardinality, 990);
+
+        let output = conn
+            .query_result("SELECT current_setting('profiling_output')")?
+            .get_string(0, 0)?;
+        assert_eq!(output.as_deref(), Some(""));
+        assert!(conn.profile("SELECT * FROM missing").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use std::{cell::OnceCell, ffi::CStr, ops::Deref};
+
+use quackdb_internal::{
+    conversion::FromDuckDb,
+    ffi,
+    handles::{DataChunkHandle, LogicalTypeHandle, QueryResultHandle},
+    type_id::TypeId,
+};
+use thiserror::Error;
+
+use crate::{data_chunk::OwnedDataChunk, types::LogicalType};
+
+/// A materialized result read without Arrow, suited to small results
+#[derive(Debug)]
+pub struct QueryResult {
+    pub handle: QueryResultHandle,
+    /// Chunks fetched on first typed access
+    chunks: OnceCell<Vec<OwnedDataChunk>>,
+}
+
+#[derive(Error, Debug)]
+pub enum QueryResultError {
+    #[error("column {0} out of range")]
+    ColumnOutOfRange(u64),
+    #[error("row {0} out of range")]
+    RowOutOfRange(u64),
+    #[error("column {column} is {actual:?}, not {expected:?}")]
+    TypeMismatch {
+        column: u64,
+        expected: TypeId,
+        actual: Option<TypeId>,
+    },
+    #[error("{0:?} values cannot be read with `get`")]
+    UnsupportedType(TypeId),
+}
+
+impl From<QueryResultHandle> for QueryResult {
+    fn from(handle: QueryResultHandle) -> Self {
+        Self {
+            handle,
+            chunks: OnceCell::new(),
+        }
+    }
+}
+
+impl QueryResult {
+    pub fn column_count(&self) -> u64 {
+        unsafe { ffi::duckdb_column_count(self.handle.as_ptr()) }
+    }
+    pub fn row_count(&self) -> u64 {
+        unsafe { ffi::duckdb_row_count(self.handle.as_ptr()) }
+    }
+    pub fn rows_changed(&self) -> u64 {
+        unsafe { ffi::duckdb_rows_changed(self.handle.as_ptr()) }
+    }
+    /// Name of a column, or `None` if out of range
+    pub fn column_name(&self, column: u64) -> Option<String> {
+        unsafe {
+            let name = ffi::duckdb_column_name(self.handle.as_ptr(), column);
+            (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+    }
+    /// Type of a column, or `None` if out of range
+    pub fn column_type(&self, column: u64) -> Option<LogicalType> {
+        if column >= self.column_count() {
+            return None;
+        }
+        let raw = unsafe { ffi::duckdb_column_logical_type(self.handle.as_ptr(), column) };
+        Some(unsafe { LogicalTypeHandle::from_raw(raw) }.into())
+    }
+    pub fn chunk_count(&self) -> u64 {
+        unsafe { ffi::duckdb_result_chunk_count(*self.handle) }
+    }
+    /// Fetch a chunk of the result, or `None` if out of range
+    pub fn result_get_chunk(&self, chunk_index: u64) -> Option<OwnedDataChunk> {
+        if chunk_index >= self.chunk_count() {
+            return None;
+        }
+        let raw = unsafe { ffi::duckdb_result_get_chunk(*self.handle, chunk_index) };
+        (!raw.is_null()).then(|| unsafe { DataChunkHandle::from_raw(raw) }.into())
+    }
+    /// Read a value, or `None` if it is null. `VARCHAR` and `BLOB` are read with
+    /// [`get_string`](Self::get_string) and [`get_bytes`](Self::get_bytes).
+    pub fn get<T: FromDuckDb>(&self, column: u64, row: u64) -> Result<Option<T>, QueryResultError>
+    where
+        T::DuckDbRepresentation: Copy,
+    {
+        if matches!(T::DUCKDB_TYPE_ID, TypeId::VarChar | TypeId::Blob) {
+            return Err(QueryResultError::UnsupportedType(T::DUCKDB_TYPE_ID));
+        }
+        self.check_type(column, &[T::DUCKDB_TYPE_ID])?;
+        let (chunk, offset) = self.locate(row)?;
+        let vector = chunk
+            .vector(column)
+            .ok_or(QueryResultError::ColumnOutOfRange(column))?;
+        Ok(unsafe { vector.read::<T>(offset) })
+    }
+    /// Read a `VARCHAR` value, or `None` if it is null
+    pub fn get_string(&self, column: u64, row: u64) -> Result<Option<String>, QueryResultError> {
+        self.check_type(column, &[TypeId::VarChar])?;
+        Ok(self
+            .read_bytes(column, row)?
+            .map(|b| String::from_utf8_lossy(&b).into_owned()))
+    }
+    /// Read a `BLOB` or `VARCHAR` value, or `None` if it is null
+    pub fn get_bytes(&self, column: u64, row: u64) -> Result<Option<Vec<u8>>, QueryResultError> {
+        self.check_type(column, &[TypeId::Blob, TypeId::VarChar])?;
+        self.read_bytes(column, row)
+    }
+
+    fn read_bytes(&self, column: u64, row: u64) -> Result<Option<Vec<u8>>, QueryResultError> {
+        let (chunk, offset) = self.locate(row)?;
+        let vector = chunk
+            .vector(column)
+            .ok_or(QueryResultError::ColumnOutOfRange(column))?;
+        Ok(unsafe { vector.read_bytes(offset) }.map(<[u8]>::to_vec))
+    }
+    fn check_type(&self, column: u64, expected: &[TypeId]) -> Result<(), QueryResultError> {
+        let actual = self
+            .column_type(column)
+            .ok_or(QueryResultError::ColumnOutOfRange(column))?
+            .type_id();
+        if !actual.is_some_and(|actual| expected.contains(&actual)) {
+            return Err(QueryResultError::TypeMismatch {
+                column,
+                expected: expected[0],
+                actual,
+            });
+        }
+        Ok(())
+    }
+    /// Chunk holding `row` and the offset of the row inside it
+    fn locate(&self, row: u64) -> Result<(&OwnedDataChunk, u64), QueryResultError> {
+        let chunks = self.chunks.get_or_init(|| {
+            (0..self.chunk_count())
+                .filter_map(|i| self.result_get_chunk(i))
+                .collect()
+        });
+        let mut offset = row;
+        for chunk in chunks {
+            if offset < chunk.size() {
+                return Ok((chunk, offset));
+            }
+            offset -= chunk.size();
+        }
+        Err(QueryResultError::RowOutOfRange(row))
+    }
+}
+
+impl Deref for QueryResult {
+    type Target = ffi::duckdb_result;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_query_result() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        let result = conn.query_result(
+            "SELECT range AS n, 'row ' || range || repeat('.', range % 20) AS s, \
+             CASE WHEN range % 2 = 0 THEN DATE '2024-01-01' END AS d, \
+             range::HUGEINT * -3 AS h \
+             FROM range(5000)",
+        )?;
+        assert_eq!(result.column_count(), 4);
+        assert_eq!(result.row_count(), 5000);
+        assert_eq!(result.column_name(1).as_deref(), Some("s"));
+        assert_eq!(result.column_name(4), None);
+        assert_eq!(
+            result.column_type(2).and_then(|t| t.type_id()),
+            Some(TypeId::Date)
+        );
+        assert!(result.chunk_count() > 1);
+        let chunk = result.result_get_chunk(0).unwrap();
+        assert_eq!(chunk.column_count(), 4);
+        assert!(result.result_get_chunk(result.chunk_count()).is_none());
+
+        assert_eq!(result.get::<i64>(0, 4321)?, Some(4321));
+        assert_eq!(result.get_string(1, 4321)?.as_deref(), Some("row 4321."));
+        assert_eq!(result.get_string(1, 7)?.as_deref(), Some("row 7......."));
+        assert_eq!(
+            result.get::<NaiveDate>(2, 10)?,
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+        );
+        assert_eq!(result.get::<NaiveDate>(2, 11)?, None);
+        assert_eq!(result.get::<i128>(3, 4000)?, Some(-12000));
+        assert!(matches!(
+            result.get::<i32>(0, 0),
+            Err(QueryResultError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            result.get::<i64>(0, 5000),
+            Err(QueryResultError::RowOutOfRange(5000))
+        ));
+        Ok(())
+    }
+}
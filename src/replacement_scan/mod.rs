@@ -1,3 +1,8 @@
+mod registry;
+pub use registry::*;
+mod router;
+pub use router::*;
+
 use std::{ffi::CString, ops::Deref};
 
 use quackdb_internal::ffi;
@@ -29,6 +34,7 @@ impl ReplacementScanInfo {
         unsafe { ffi::duckdb_replacement_scan_set_function_name(**self, cstr.as_ptr()) }
         Ok(())
     }
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     pub fn add_parameter(&self, parameter: ffi::duckdb_value) {
         unsafe { ffi::duckdb_replacement_scan_add_parameter(**self, parameter) }
     }
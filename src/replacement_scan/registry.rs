@@ -0,0 +1,274 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, PoisonError, RwLock,
+    },
+};
+
+use thiserror::Error;
+
+use super::{Replacement, ReplacementScanError, ReplacementScanInfo, ReplacementScanRouter};
+
+/// Number of resolutions kept in the debug log of a [`ReplacementScanRegistry`]
+pub const RESOLUTION_LOG_CAPACITY: usize = 1024;
+
+/// A handler of a [`ReplacementScanRegistry`]
+pub trait ReplacementScan: Send + Sync + 'static {
+    /// Table function call replacing `table_name`, or `None` to let the next handler try
+    fn replace(
+        &self,
+        table_name: &str,
+    ) -> Result<Option<Replacement>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl<F> ReplacementScan for F
+where
+    F: Fn(&str) -> Option<Replacement> + Send + Sync + 'static,
+{
+    fn replace(
+        &self,
+        table_name: &str,
+    ) -> Result<Option<Replacement>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self(table_name))
+    }
+}
+
+impl ReplacementScan for ReplacementScanRouter {
+    fn replace(
+        &self,
+        table_name: &str,
+    ) -> Result<Option<Replacement>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.resolve(table_name))
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("replacement scan `{name}` failed: {source}")]
+pub struct ReplacementScanHandlerError {
+    pub name: String,
+    pub source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+struct Entry {
+    id: u64,
+    name: String,
+    priority: i32,
+    enabled: AtomicBool,
+    scan: Box<dyn ReplacementScan>,
+}
+
+/// Outcome of one table name lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub table_name: String,
+    /// Name of the handler which replaced the table, if any
+    pub handler: Option<String>,
+}
+
+/// Replacement scan handlers of a database, tried by descending priority.
+///
+/// Handlers of equal priority are tried in registration order. The registry is installed as
+/// a single replacement scan the first time [`Database::register_replacement_scan`] is called.
+///
+/// [`Database::register_replacement_scan`]: crate::database::Database::register_replacement_scan
+#[derive(Default)]
+pub struct ReplacementScanRegistry {
+    entries: RwLock<Vec<Arc<Entry>>>,
+    next_id: AtomicU64,
+    log: Mutex<VecDeque<Resolution>>,
+}
+
+impl fmt::Debug for ReplacementScanRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        f.debug_list()
+            .entries(entries.iter().map(|e| (&e.name, e.priority)))
+            .finish()
+    }
+}
+
+impl ReplacementScanRegistry {
+    pub(crate) fn register<S: ReplacementScan>(
+        &self,
+        name: &str,
+        priority: i32,
+        scan: S,
+    ) -> ReplacementScanHandle {
+        let entry = Arc::new(Entry {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            name: name.to_owned(),
+            priority,
+            enabled: AtomicBool::new(true),
+            scan: Box::new(scan),
+        });
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        entries.push(entry.clone());
+        entries.sort_by_key(|e| (std::cmp::Reverse(e.priority), e.id));
+        ReplacementScanHandle { entry }
+    }
+    /// Names of registered handlers in dispatch order
+    pub fn handlers(&self) -> Vec<String> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        entries.iter().map(|e| e.name.clone()).collect()
+    }
+    /// Recent resolutions, oldest first
+    pub fn log(&self) -> Vec<Resolution> {
+        let log = self.log.lock().unwrap_or_else(PoisonError::into_inner);
+        log.iter().cloned().collect()
+    }
+    pub fn clear_log(&self) {
+        self.log
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+    /// Find the first enabled handler replacing `table_name` and return its name and replacement
+    pub fn resolve(
+        &self,
+        table_name: &str,
+    ) -> Result<Option<(String, Replacement)>, ReplacementScanHandlerError> {
+        // Handlers may register or toggle handlers, so the lock is not held while they run
+        let entries = self
+            .entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut resolved = None;
+        for entry in entries {
+            if !entry.enabled.load(Ordering::Relaxed) {
+                continue;
+            }
+            let replacement =
+                entry
+                    .scan
+                    .replace(table_name)
+                    .map_err(|source| ReplacementScanHandlerError {
+                        name: entry.name.clone(),
+                        source,
+                    })?;
+            if let Some(replacement) = replacement {
+                resolved = Some((entry.name.clone(), replacement));
+                break;
+            }
+        }
+        self.record(Resolution {
+            table_name: table_name.to_owned(),
+            handler: resolved.as_ref().map(|(name, _)| name.clone()),
+        });
+        Ok(resolved)
+    }
+    fn record(&self, resolution: Resolution) {
+        let mut log = self.log.lock().unwrap_or_else(PoisonError::into_inner);
+        if log.len() == RESOLUTION_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(resolution);
+    }
+    /// Replacement scan callback, see [`Database::add_replacement_scan`](crate::database::Database::add_replacement_scan)
+    pub(crate) fn replace(
+        &self,
+        info: &ReplacementScanInfo,
+        table_name: &str,
+    ) -> Result<(), ReplacementScanError<ReplacementScanHandlerError>> {
+        match self.resolve(table_name)? {
+            Some((_, replacement)) => replacement.apply(info),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Handle of a registered replacement scan, used to toggle it at runtime.
+///
+/// Dropping the handle keeps the handler registered.
+#[derive(Clone)]
+pub struct ReplacementScanHandle {
+    entry: Arc<Entry>,
+}
+
+impl fmt::Debug for ReplacementScanHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplacementScanHandle")
+            .field("name", &self.entry.name)
+            .field("priority", &self.entry.priority)
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl ReplacementScanHandle {
+    pub fn name(&self) -> &str {
+        &self.entry.name
+    }
+    pub fn priority(&self) -> i32 {
+        self.entry.priority
+    }
+    pub fn is_enabled(&self) -> bool {
+        self.entry.enabled.load(Ordering::Relaxed)
+    }
+    pub fn set_enabled(&self, enabled: bool) {
+        self.entry.enabled.store(enabled, Ordering::Relaxed)
+    }
+    pub fn enable(&self) {
+        self.set_enabled(true)
+    }
+    pub fn disable(&self) {
+        self.set_enabled(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{array::AsArray, datatypes::Int64Type, error::ArrowError};
+
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    fn count(conn: &crate::connection::Connection, sql: &str) -> Result<i64, QuackError> {
+        let batches = conn
+            .query(sql)?
+            .into_stream()?
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        Ok(batches[0].column(0).as_primitive::<Int64Type>().value(0))
+    }
+
+    #[test]
+    fn test_registry() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let low = db.register_replacement_scan("low", 0, |name: &str| {
+            name.ends_with(".dat")
+                .then(|| Replacement::new("range").arg(1i64))
+        });
+        let high = db.register_replacement_scan("high", 10, |name: &str| {
+            name.starts_with("hot/")
+                .then(|| Replacement::new("range").arg(2i64))
+        });
+        let mut router = ReplacementScanRouter::new();
+        router.glob("*.dat", |_| Replacement::new("range").arg(3i64));
+        db.register_replacement_scan("router", 0, router);
+        assert_eq!(db.replacement_scans().handlers(), ["high", "low", "router"]);
+
+        let conn = db.connect()?;
+        assert_eq!(count(&conn, "SELECT count(*) FROM 'hot/a.dat'")?, 2);
+        high.disable();
+        assert_eq!(count(&conn, "SELECT count(*) FROM 'hot/a.dat'")?, 1);
+        low.disable();
+        assert_eq!(count(&conn, "SELECT count(*) FROM 'hot/a.dat'")?, 3);
+        high.enable();
+        assert!(conn.query("SELECT * FROM 'cold.xyz'").is_err());
+
+        let log = db.replacement_scans().log();
+        let handlers: Vec<_> = log
+            .iter()
+            .filter(|r| r.table_name == "hot/a.dat")
+            .map(|r| r.handler.as_deref())
+            .collect();
+        assert_eq!(handlers, [Some("high"), Some("low"), Some("router")]);
+        assert!(log
+            .iter()
+            .any(|r| r.table_name == "cold.xyz" && r.handler.is_none()));
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use std::{convert::Infallible, fmt};
+
+use crate::value::Value;
+
+use super::{ReplacementScanError, ReplacementScanInfo};
+
+/// Table function call replacing a table name
+#[derive(Debug)]
+pub struct Replacement {
+    function_name: String,
+    parameters: Vec<Value>,
+}
+
+impl Replacement {
+    pub fn new(function_name: impl Into<String>) -> Self {
+        Self {
+            function_name: function_name.into(),
+            parameters: Vec::new(),
+        }
+    }
+    /// Append a positional argument of the table function
+    pub fn arg(mut self, value: impl Into<Value>) -> Self {
+        self.parameters.push(value.into());
+        self
+    }
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+    pub fn parameters(&self) -> &[Value] {
+        &self.parameters
+    }
+    /// Replace the scan described by `info` with this table function call
+    pub fn apply<E>(&self, info: &ReplacementScanInfo) -> Result<(), ReplacementScanError<E>> {
+        info.set_function_name(&self.function_name)?;
+        for parameter in &self.parameters {
+            // duckdb copies the value
+            info.add_parameter(**parameter);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    /// `*` matches any sequence of characters, including `/`, and `?` matches one character
+    Glob(String),
+    /// Matches `scheme://...`
+    Scheme(String),
+}
+
+impl Pattern {
+    /// The part of `table_name` passed to the handler, if it matches
+    fn matches<'a>(&self, table_name: &'a str) -> Option<&'a str> {
+        match self {
+            Pattern::Glob(glob) => glob_match(glob, table_name).then_some(table_name),
+            Pattern::Scheme(scheme) => table_name
+                .strip_prefix(scheme.as_str())
+                .and_then(|rest| rest.strip_prefix("://")),
+        }
+    }
+}
+
+type Handler = Box<dyn Fn(&str) -> Replacement + Send + Sync>;
+
+struct Route {
+    pattern: Pattern,
+    handler: Handler,
+}
+
+/// Routes table names to table functions by glob pattern or URL scheme.
+///
+/// Routes are tried in the order they are added, and the first match wins. Install the router
+/// with [`Database::add_replacement_scan_router`](crate::database::Database::add_replacement_scan_router).
+#[derive(Default)]
+pub struct ReplacementScanRouter {
+    routes: Vec<Route>,
+}
+
+impl fmt::Debug for ReplacementScanRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.routes.iter().map(|r| &r.pattern))
+            .finish()
+    }
+}
+
+impl ReplacementScanRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Route table names matching `pattern`, e.g. `*.myfmt` or `logs/*.jsonl`.
+    /// The handler receives the whole table name.
+    pub fn glob<F>(&mut self, pattern: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&str) -> Replacement + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            pattern: Pattern::Glob(pattern.to_owned()),
+            handler: Box::new(handler),
+        });
+        self
+    }
+    /// Route table names such as `mystore://bucket/key`.
+    /// The handler receives the part after `://`, e.g. `bucket/key`.
+    pub fn scheme<F>(&mut self, scheme: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&str) -> Replacement + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            pattern: Pattern::Scheme(scheme.to_owned()),
+            handler: Box::new(handler),
+        });
+        self
+    }
+    /// Route table names matching `pattern` to `function_name(table_name)`
+    pub fn glob_function(&mut self, pattern: &str, function_name: &str) -> &mut Self {
+        let function_name = function_name.to_owned();
+        self.glob(pattern, move |table_name| {
+            Replacement::new(function_name.clone()).arg(table_name)
+        })
+    }
+    /// Table function call replacing `table_name`, if any route matches
+    pub fn resolve(&self, table_name: &str) -> Option<Replacement> {
+        self.routes.iter().find_map(|route| {
+            let matched = route.pattern.matches(table_name)?;
+            Some((route.handler)(matched))
+        })
+    }
+    /// Replacement scan callback, see [`Database::add_replacement_scan`](crate::database::Database::add_replacement_scan)
+    pub(crate) fn replace(
+        &self,
+        info: &ReplacementScanInfo,
+        table_name: &str,
+    ) -> Result<(), ReplacementScanError<Infallible>> {
+        match self.resolve(table_name) {
+            Some(replacement) => replacement.apply(info),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Match `text` against a glob where `*` matches any sequence and `?` matches one character
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently consumes up to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` consume one more character
+                Some((sp, st)) => {
+                    star = Some((sp, st + 1));
+                    p = sp + 1;
+                    t = st + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{array::AsArray, datatypes::Int64Type, error::ArrowError};
+
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.myfmt", "data/events.myfmt"));
+        assert!(glob_match("logs/*.jsonl", "logs/2024-01.jsonl"));
+        assert!(glob_match("a?c*", "abcdef"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*.myfmt", "events.myfmt.gz"));
+        assert!(!glob_match("logs/*.jsonl", "other/a.jsonl"));
+        assert!(!glob_match("a?c", "ac"));
+    }
+
+    #[test]
+    fn test_router() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let mut router = ReplacementScanRouter::new();
+        router
+            .scheme("numbers", |rest| {
+                Replacement::new("range").arg(rest.parse::<i64>().unwrap_or(0))
+            })
+            .glob("*.myfmt", |_| Replacement::new("range").arg(3i64))
+            .glob("data/*", |_| Replacement::new("range").arg(100i64));
+        assert!(router.resolve("events.csv").is_none());
+        assert_eq!(
+            router.resolve("data/events.myfmt").unwrap().function_name(),
+            "range"
+        );
+        db.add_replacement_scan_router(router);
+        let conn = db.connect()?;
+        for (sql, count) in [
+            ("SELECT count(*) FROM 'data/events.myfmt'", 3),
+            ("SELECT count(*) FROM 'numbers://42'", 42),
+        ] {
+            let batches = conn
+                .query(sql)?
+                .into_stream()?
+                .collect::<Result<Vec<_>, ArrowError>>()?;
+            assert_eq!(
+                batches[0].column(0).as_primitive::<Int64Type>().value(0),
+                count
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+//! Helpers to build SQL text
+
+use std::{iter::Peekable, str::Chars};
+
+/// Quote an identifier, e.g. a table or column name
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quote a string literal
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Quote a possibly qualified name, e.g. `["main", "my table"]` as `"main"."my table"`
+pub fn quote_qualified<S: AsRef<str>>(parts: &[S]) -> String {
+    parts
+        .iter()
+        .map(|part| quote_identifier(part.as_ref()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Replace string and numeric literals with `?`, so SQL text can be logged without the
+/// data it carries. Quoted identifiers and `$n` parameters are kept.
+///
+/// Strings may be quoted as `'...'`, with backslash escapes as `E'...'`, or with dollars as
+/// `$$...$$` and `$tag$...$tag$`. An unterminated string redacts the rest of the text.
+pub fn redact_literals(sql: &str) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    // Whether the previous character continues a word, so digits belong to it
+    let mut in_word = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                redacted.push('?');
+                in_word = false;
+            }
+            'E' | 'e' if !in_word && chars.peek() == Some(&'\'') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == '\\' {
+                        chars.next();
+                    } else if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                redacted.push('?');
+                in_word = false;
+            }
+            '$' if !in_word => match dollar_quote_tag(&chars) {
+                Some(tag) => {
+                    // Skip the tag and the `$` closing it
+                    chars.nth(tag.chars().count());
+                    let delimiter = format!("${tag}$");
+                    let mut body = String::new();
+                    while !body.ends_with(&delimiter) {
+                        match chars.next() {
+                            Some(c) => body.push(c),
+                            None => break,
+                        }
+                    }
+                    redacted.push('?');
+                    in_word = false;
+                }
+                None => {
+                    redacted.push(c);
+                    in_word = true;
+                }
+            },
+            '"' => {
+                redacted.push(c);
+                while let Some(c) = chars.next() {
+                    redacted.push(c);
+                    if c == '"' {
+                        match chars.next_if_eq(&'"') {
+                            Some(c) => redacted.push(c),
+                            None => break,
+                        }
+                    }
+                }
+                in_word = true;
+            }
+            c if c.is_ascii_digit() && !in_word => {
+                while chars
+                    .next_if(|c| c.is_alphanumeric() || *c == '.' || *c == '_')
+                    .is_some()
+                {}
+                redacted.push('?');
+                in_word = false;
+            }
+            c => {
+                redacted.push(c);
+                in_word = c.is_alphanumeric() || c == '_' || c == '$';
+            }
+        }
+    }
+    redacted
+}
+
+/// Replace the parameters of a statement with SQL text, e.g. literals of their values.
+///
+/// `replacement` receives the name of each parameter as DuckDB reports it: the position
+/// for `?`, counting from 1, and for `$1`, or the name for `$name`. Parameters it returns
+/// `None` for are kept, as is everything inside strings, quoted identifiers and comments.
+pub(crate) fn replace_parameters(
+    sql: &str,
+    replacement: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut replaced = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut in_word = false;
+    let mut position = 0;
+    while let Some(c) = chars.next() {
+        replaced.push(c);
+        match c {
+            '\'' | '"' => {
+                copy_quoted(&mut chars, &mut replaced, c, false);
+                in_word = c == '"';
+            }
+            'E' | 'e' if !in_word && chars.peek() == Some(&'\'') => {
+                replaced.extend(chars.next());
+                copy_quoted(&mut chars, &mut replaced, '\'', true);
+                in_word = false;
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    replaced.push(c);
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                in_word = false;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                replaced.extend(chars.next());
+                let mut comment = String::new();
+                while !comment.ends_with("*/") {
+                    match chars.next() {
+                        Some(c) => comment.push(c),
+                        None => break,
+                    }
+                }
+                replaced.push_str(&comment);
+                in_word = false;
+            }
+            '?' => {
+                replaced.pop();
+                position += 1;
+                replaced.push_str(&replacement(&position.to_string()).unwrap_or("?".to_owned()));
+                in_word = false;
+            }
+            '$' if !in_word => match dollar_quote_tag(&chars) {
+                Some(tag) => {
+                    let delimiter = format!("${tag}$");
+                    replaced.extend(chars.by_ref().take(tag.chars().count() + 1));
+                    let mut body = String::new();
+                    while !body.ends_with(&delimiter) {
+                        match chars.next() {
+                            Some(c) => body.push(c),
+                            None => break,
+                        }
+                    }
+                    replaced.push_str(&body);
+                    in_word = false;
+                }
+                None => {
+                    let name: String =
+                        std::iter::from_fn(|| chars.next_if(|c| c.is_alphanumeric() || *c == '_'))
+                            .collect();
+                    match replacement(&name) {
+                        Some(value) => {
+                            replaced.pop();
+                            replaced.push_str(&value);
+                        }
+                        None => replaced.push_str(&name),
+                    }
+                    in_word = true;
+                }
+            },
+            c => in_word = c.is_alphanumeric() || c == '_' || c == '$',
+        }
+    }
+    replaced
+}
+
+/// Copy a string or quoted identifier up to and including its closing `quote`, after the
+/// opening quote was copied
+fn copy_quoted(chars: &mut Peekable<Chars>, out: &mut String, quote: char, escapes: bool) {
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if escapes && c == '\\' {
+            out.extend(chars.next());
+        } else if c == quote {
+            match chars.next_if_eq(&quote) {
+                Some(c) => out.push(c),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Tag of a dollar quote opened by the `$` just read, e.g. `tag` for `$tag$` and an empty
+/// tag for `$$`, or `None` if the `$` starts a parameter such as `$1` or `$name`
+fn dollar_quote_tag(chars: &Peekable<Chars>) -> Option<String> {
+    let mut ahead = chars.clone();
+    let tag: String =
+        std::iter::from_fn(|| ahead.next_if(|c| c.is_alphanumeric() || *c == '_')).collect();
+    if tag.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    ahead.next_if_eq(&'$').map(|_| tag)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("t"), r#""t""#);
+        assert_eq!(quote_identifier(r#"a "b""#), r#""a ""b""""#);
+        assert_eq!(
+            quote_qualified(&["main", "my table"]),
+            r#""main"."my table""#
+        );
+    }
+
+    #[test]
+    fn test_redact_literals() {
+        assert_eq!(
+            redact_literals("SELECT * FROM t2 WHERE name = 'it''s' AND x > 1.5 AND y = $1"),
+            "SELECT * FROM t2 WHERE name = ? AND x > ? AND y = $1"
+        );
+        assert_eq!(
+            redact_literals(r#"INSERT INTO "a 'b" VALUES (10, -2e3, ?)"#),
+            r#"INSERT INTO "a 'b" VALUES (?, -?, ?)"#
+        );
+        assert_eq!(
+            redact_literals(r"SELECT e, E'it\'s', e'a\\', x'1' FROM t"),
+            "SELECT e, ?, ?, x? FROM t"
+        );
+        assert_eq!(
+            redact_literals("SELECT $$it's$$, $tag$a $$ b' $tag$, $1, $name FROM t"),
+            "SELECT ?, ?, $1, $name FROM t"
+        );
+        assert_eq!(redact_literals("SELECT $q$secret"), "SELECT ?");
+    }
+
+    #[test]
+    fn test_replace_parameters() {
+        let values = |name: &str| (name != "2").then(|| format!("<{name}>"));
+        assert_eq!(
+            replace_parameters(
+                "SELECT ?, '?', \"?\", ? -- why?\nFROM t WHERE x = ?",
+                values
+            ),
+            "SELECT <1>, '?', \"?\", ? -- why?\nFROM t WHERE x = <3>"
+        );
+        assert_eq!(
+            replace_parameters(
+                "SELECT $1, $x, a$1, $$ $1 $$, E'\\' $1', /* $1 */ $x_1",
+                values
+            ),
+            "SELECT <1>, <x>, a$1, $$ $1 $$, E'\\' $1', /* $1 */ <x_1>"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use arrow::{
+    array::{Array, AsArray},
+    datatypes::*,
+    record_batch::RecordBatch,
+};
+use quackdb_internal::{conversion::IntoDuckDb, ffi};
+
+use super::{PreparedStatement, PreparedStatementError};
+
+impl PreparedStatement {
+    /// Execute once per row of `batch` inside one transaction and return the total number of
+    /// rows changed.
+    ///
+    /// Columns bind by name if every column name is a parameter name, e.g. `$id`, and by
+    /// position otherwise.
+    pub fn execute_batch_arrow(
+        &mut self,
+        batch: &RecordBatch,
+    ) -> Result<u64, PreparedStatementError> {
+        let indices = self.arrow_parameter_indices(batch)?;
+        self.transaction(|stmt| {
+            let mut changed = 0;
+            for row in 0..batch.num_rows() {
+                let result = stmt.clear_bindings().and_then(|_| {
+                    for (column, &idx) in batch.columns().iter().zip(&indices) {
+                        stmt.bind_arrow(column.as_ref(), row, idx)?;
+                    }
+                    Ok(stmt.execute_result()?.rows_changed())
+                });
+                changed += result.map_err(|e| PreparedStatementError::RowError {
+                    row,
+                    source: Box::new(e),
+                })?;
+            }
+            Ok(changed)
+        })
+    }
+
+    /// Parameter index of each column of `batch`
+    fn arrow_parameter_indices(
+        &self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<u64>, PreparedStatementError> {
+        let schema = batch.schema();
+        let named: Option<Vec<u64>> = schema
+            .fields()
+            .iter()
+            .map(|f| self.parameter_index(f.name()).ok())
+            .collect();
+        if let Some(indices) = named {
+            return Ok(indices);
+        }
+        let nparams = self.nparams();
+        if batch.num_columns() as u64 != nparams {
+            return Err(PreparedStatementError::ParameterCountMismatch(
+                batch.num_columns(),
+                nparams,
+            ));
+        }
+        Ok((1..=nparams).collect())
+    }
+
+    /// Bind the value of `array` at `row` to a parameter
+    fn bind_arrow(
+        &mut self,
+        array: &dyn Array,
+        row: usize,
+        param_idx: u64,
+    ) -> Result<(), PreparedStatementError> {
+        if array.is_null(row) {
+            return self.bind_at(None::<bool>, param_idx);
+        }
+        match array.data_type() {
+            DataType::Boolean => self.bind_at(array.as_boolean().value(row), param_idx),
+            DataType::Int8 => self.bind_at(array.as_primitive::<Int8Type>().value(row), param_idx),
+            DataType::Int16 => {
+                self.bind_at(array.as_primitive::<Int16Type>().value(row), param_idx)
+            }
+            DataType::Int32 => {
+                self.bind_at(array.as_primitive::<Int32Type>().value(row), param_idx)
+            }
+            DataType::Int64 => {
+                self.bind_at(array.as_primitive::<Int64Type>().value(row), param_idx)
+            }
+            DataType::UInt8 => {
+                self.bind_at(array.as_primitive::<UInt8Type>().value(row), param_idx)
+            }
+            DataType::UInt16 => {
+                self.bind_at(array.as_primitive::<UInt16Type>().value(row), param_idx)
+            }
+            DataType::UInt32 => {
+                self.bind_at(array.as_primitive::<UInt32Type>().value(row), param_idx)
+            }
+            DataType::UInt64 => {
+                self.bind_at(array.as_primitive::<UInt64Type>().value(row), param_idx)
+            }
+            DataType::Float32 => {
+                self.bind_at(array.as_primitive::<Float32Type>().value(row), param_idx)
+            }
+            DataType::Float64 => {
+                self.bind_at(array.as_primitive::<Float64Type>().value(row), param_idx)
+            }
+            DataType::Utf8 => self.bind_at(array.as_string::<i32>().value(row), param_idx),
+            DataType::LargeUtf8 => self.bind_at(array.as_string::<i64>().value(row), param_idx),
+            DataType::Binary => self.bind_at(array.as_binary::<i32>().value(row), param_idx),
+            DataType::LargeBinary => self.bind_at(array.as_binary::<i64>().value(row), param_idx),
+            DataType::Date32 => self.bind_at(
+                array.as_primitive::<Date32Type>().value_as_date(row),
+                param_idx,
+            ),
+            DataType::Timestamp(TimeUnit::Second, None) => self.bind_at(
+                array
+                    .as_primitive::<TimestampSecondType>()
+                    .value_as_datetime(row),
+                param_idx,
+            ),
+            DataType::Timestamp(TimeUnit::Millisecond, None) => self.bind_at(
+                array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value_as_datetime(row),
+                param_idx,
+            ),
+            DataType::Timestamp(TimeUnit::Microsecond, None) => self.bind_at(
+                array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .value_as_datetime(row),
+                param_idx,
+            ),
+            DataType::Timestamp(TimeUnit::Nanosecond, None) => self.bind_at(
+                array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value_as_datetime(row),
+                param_idx,
+            ),
+            &DataType::Decimal128(width, scale) if scale >= 0 => {
+                self.check_param_index(param_idx)?;
+                let decimal = ffi::duckdb_decimal {
+                    width,
+                    scale: scale as u8,
+                    value: array
+                        .as_primitive::<Decimal128Type>()
+                        .value(row)
+                        .into_duckdb(),
+                };
+                match unsafe { ffi::duckdb_bind_decimal(**self, param_idx, decimal) } {
+                    ffi::DuckDBSuccess => Ok(()),
+                    _ => Err(PreparedStatementError::BindError(
+                        "duckdb_bind_decimal()",
+                        param_idx,
+                    )),
+                }
+            }
+            other => Err(PreparedStatementError::UnsupportedArrowType(other.clone())),
+        }
+    }
+}
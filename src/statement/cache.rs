@@ -0,0 +1,117 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use quackdb_internal::handles::PreparedStatementHandle;
+
+/// Default number of statements kept by [`StatementCache`]
+pub const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 16;
+
+/// Least recently used prepared statements of a connection, keyed by SQL text
+#[derive(Debug)]
+pub(crate) struct StatementCache {
+    capacity: usize,
+    /// Most recently used first
+    entries: VecDeque<(String, Arc<PreparedStatementHandle>)>,
+}
+
+impl Default for StatementCache {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            entries: VecDeque::new(),
+        }
+    }
+}
+
+impl StatementCache {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.entries.truncate(capacity);
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+    /// Cached statement for `sql`, marked as most recently used
+    pub fn get(&mut self, sql: &str) -> Option<Arc<PreparedStatementHandle>> {
+        let position = self.entries.iter().position(|(s, _)| s == sql)?;
+        let entry = self.entries.remove(position)?;
+        let handle = entry.1.clone();
+        self.entries.push_front(entry);
+        Some(handle)
+    }
+    pub fn remove(&mut self, sql: &str) {
+        self.entries.retain(|(s, _)| s != sql);
+    }
+    pub fn insert(&mut self, sql: &str, handle: Arc<PreparedStatementHandle>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|(s, _)| s != sql);
+        self.entries.push_front((sql.to_owned(), handle));
+        self.entries.truncate(self.capacity);
+    }
+}
+
+/// Whether any statement in `sql` changes the schema. Comments, literals and quoted
+/// identifiers are skipped.
+pub(crate) fn is_ddl(sql: &str) -> bool {
+    const DDL: [&str; 6] = ["CREATE", "DROP", "ALTER", "ATTACH", "DETACH", "IMPORT"];
+    let mut chars = sql.chars().peekable();
+    let mut statement_start = true;
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.next_if_eq(&'-').is_some() => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                while let Some(c) = chars.next() {
+                    if c == '*' && chars.next_if_eq(&'/').is_some() {
+                        break;
+                    }
+                }
+            }
+            '\'' | '"' => {
+                // A doubled quote reopens the literal, which this loop handles alike
+                while chars.next_if(|&n| n != c).is_some() {}
+                chars.next();
+                statement_start = false;
+            }
+            ';' => statement_start = true,
+            c if c.is_whitespace() || (c == '(' && statement_start) => {}
+            c if c.is_ascii_alphabetic() => {
+                let mut keyword = String::from(c);
+                while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+                    keyword.push(c);
+                }
+                if statement_start && DDL.iter().any(|k| keyword.eq_ignore_ascii_case(k)) {
+                    return true;
+                }
+                statement_start = false;
+            }
+            _ => statement_start = false,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_ddl() {
+        assert!(is_ddl("CREATE TABLE t(i INTEGER)"));
+        assert!(is_ddl("  drop view v"));
+        assert!(is_ddl("\nAlter table t rename to u"));
+        assert!(!is_ddl("SELECT * FROM created"));
+        assert!(!is_ddl("INSERT INTO t VALUES (1)"));
+        assert!(!is_ddl(""));
+        assert!(is_ddl("-- comment\nDROP TABLE t"));
+        assert!(is_ddl("/* a; DROP */ (CREATE TABLE t AS SELECT 1)"));
+        assert!(is_ddl("INSERT INTO t VALUES (1); drop table t"));
+        assert!(!is_ddl("SELECT 'x; DROP TABLE t' AS \"; create\""));
+        assert!(!is_ddl("SELECT 1 -- ; DROP TABLE t"));
+    }
+}
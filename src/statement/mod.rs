@@ -0,0 +1,482 @@
+mod arrow;
+mod cache;
+pub use cache::DEFAULT_STATEMENT_CACHE_CAPACITY;
+pub(crate) use cache::{is_ddl, StatementCache};
+mod params;
+pub use params::*;
+pub use quackdb_internal::conversion::{BindParam, Struct, StructFields, ToDuckDbValue, ToStruct};
+
+use std::{
+    collections::BTreeMap,
+    ffi::{CStr, CString},
+    fmt,
+    ops::Deref,
+    sync::Arc,
+};
+
+use ::arrow::datatypes::DataType;
+use cstr::cstr;
+
+use quackdb_internal::{
+    ffi,
+    handles::{ArrowResultHandle, PendingResultHandle, PreparedStatementHandle, QueryResultHandle},
+};
+
+use crate::{
+    arrow::{ArrowResult, StreamingResult},
+    query_result::QueryResult,
+    sql::redact_literals,
+    trace::span,
+};
+
+#[derive(Debug)]
+pub struct PreparedStatement {
+    pub handle: Arc<PreparedStatementHandle>,
+    current_index: u64,
+    bindings: Bindings,
+}
+
+/// Owned copies of the bound values by parameter index, `None` for values that cannot be
+/// copied
+#[derive(Default)]
+struct Bindings(BTreeMap<u64, Option<Box<dyn BindParam>>>);
+
+impl fmt::Debug for Bindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PreparedStatementError {
+    #[error("duckdb_clear_bindings() failed")]
+    ClearBindingsError,
+    #[error("{0}: binding parameter to column {1} failed")]
+    BindError(&'static str, u64),
+    #[error("attempted binding to column {0} outside bounds 1..={1}")]
+    BindOutOfBound(u64, u64),
+    #[error("execute failed: {0}")]
+    ExecuteError(String),
+    #[error("unknown parameter name: {0}")]
+    UnknownParameter(String),
+    #[error("parameter {0} is bound to a value that cannot be copied")]
+    UncopyableParameter(u64),
+    #[error("{0} columns cannot bind to {1} parameters")]
+    ParameterCountMismatch(usize, u64),
+    #[error("cannot bind arrow type {0}")]
+    UnsupportedArrowType(DataType),
+    #[error("transaction error: {0}")]
+    TransactionError(String),
+    #[error("row {row}: {source}")]
+    RowError {
+        row: usize,
+        source: Box<PreparedStatementError>,
+    },
+}
+
+impl PreparedStatement {
+    pub fn nparams(&self) -> u64 {
+        unsafe { ffi::duckdb_nparams(**self) }
+    }
+    // /// # Safety
+    // /// * `param_idx` must be in range
+    // pub unsafe fn param_type(&self, param_idx: u64) -> TypeId {
+    //     let ty = ffi::duckdb_param_type(self.handle, param_idx);
+    //     TypeId::from_raw(ty).expect("invalid duckdb type")
+    // }
+    pub fn clear_bindings(&mut self) -> Result<(), PreparedStatementError> {
+        self.bindings.0.clear();
+        unsafe {
+            let res = ffi::duckdb_clear_bindings(**self);
+            if res != ffi::DuckDBSuccess {
+                return Err(PreparedStatementError::ClearBindingsError);
+            }
+            Ok(())
+        }
+    }
+    /// Bind one parameter at the next position
+    pub fn bind<T: BindParam>(&mut self, param: T) -> Result<&mut Self, PreparedStatementError> {
+        self.bind_at(param, self.current_index)?;
+        self.current_index += 1;
+        Ok(self)
+    }
+    /// Reset current position. Parameters already bound are kept.
+    pub fn reset(&mut self) -> &mut Self {
+        self.set_position(1)
+    }
+    pub fn set_position(&mut self, param_idx: u64) -> &mut Self {
+        self.current_index = param_idx;
+        self
+    }
+    /// Bind one paramer at specified position
+    pub fn bind_at<T: BindParam>(
+        &mut self,
+        param: T,
+        param_idx: u64,
+    ) -> Result<(), PreparedStatementError> {
+        self.check_param_index(param_idx)?;
+        unsafe { param.bind_param_unchecked(**self, param_idx) }
+            .map_err(|e| PreparedStatementError::BindError(e, param_idx))?;
+        self.bindings.0.insert(param_idx, param.to_owned_param());
+        Ok(())
+    }
+    /// Bind the values bound to `source`, e.g. to a statement prepared from related SQL
+    pub(crate) fn copy_bindings_from(
+        &mut self,
+        source: &PreparedStatement,
+    ) -> Result<(), PreparedStatementError> {
+        for (&param_idx, param) in &source.bindings.0 {
+            let param = param
+                .as_deref()
+                .ok_or(PreparedStatementError::UncopyableParameter(param_idx))?;
+            self.bind_at(param, param_idx)?;
+        }
+        Ok(())
+    }
+    /// Bind one parameter by name, e.g. `id` for `$id`
+    pub fn bind_named<T: BindParam>(
+        &mut self,
+        name: &str,
+        param: T,
+    ) -> Result<(), PreparedStatementError> {
+        let param_idx = self.parameter_index(name)?;
+        self.bind_at(param, param_idx)
+    }
+    /// Position of a named parameter
+    pub fn parameter_index(&self, name: &str) -> Result<u64, PreparedStatementError> {
+        let c_name = CString::new(name)
+            .map_err(|_| PreparedStatementError::UnknownParameter(name.to_owned()))?;
+        let mut param_idx = 0;
+        let r =
+            unsafe { ffi::duckdb_bind_parameter_index(**self, &mut param_idx, c_name.as_ptr()) };
+        if r != ffi::DuckDBSuccess {
+            return Err(PreparedStatementError::UnknownParameter(name.to_owned()));
+        }
+        Ok(param_idx)
+    }
+    /// Name of a parameter, e.g. `id` for `$id`, or its position for `?` and `$1`
+    pub fn parameter_name(&self, param_idx: u64) -> Option<String> {
+        unsafe {
+            let ptr = ffi::duckdb_parameter_name(**self, param_idx);
+            if ptr.is_null() {
+                return None;
+            }
+            let name = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            ffi::duckdb_free(ptr as _);
+            Some(name)
+        }
+    }
+    fn check_param_index(&self, param_idx: u64) -> Result<(), PreparedStatementError> {
+        let nparams = self.nparams();
+        if !(1..=nparams).contains(&param_idx) {
+            return Err(PreparedStatementError::BindOutOfBound(param_idx, nparams));
+        }
+        Ok(())
+    }
+    /// Clear bindings, bind a full set of parameters and execute
+    pub(crate) fn execute_params<P: Params>(
+        &mut self,
+        params: P,
+    ) -> Result<u64, PreparedStatementError> {
+        self.clear_bindings()?;
+        params.bind_to(self)?;
+        Ok(self.execute_result()?.rows_changed())
+    }
+    /// Execute once per set of parameters inside one transaction and return the total number
+    /// of rows changed. A failure rolls back all rows and reports the index of the failing row.
+    ///
+    /// If a transaction is already open on the connection, it is used instead.
+    pub fn execute_many<P: Params>(
+        &mut self,
+        params: impl IntoIterator<Item = P>,
+    ) -> Result<u64, PreparedStatementError> {
+        self.transaction(|stmt| {
+            let mut changed = 0;
+            for (row, params) in params.into_iter().enumerate() {
+                changed +=
+                    stmt.execute_params(params)
+                        .map_err(|e| PreparedStatementError::RowError {
+                            row,
+                            source: Box::new(e),
+                        })?;
+            }
+            Ok(changed)
+        })
+    }
+    /// Run `f` in a new transaction, committing on success and rolling back on failure
+    fn transaction<R>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<R, PreparedStatementError>,
+    ) -> Result<R, PreparedStatementError> {
+        // A transaction already open is left to its owner. It must be detected up front, as
+        // a failing `BEGIN` would abort it.
+        let owned = !self.in_transaction()?;
+        if owned {
+            self.run_on_connection(cstr!("BEGIN TRANSACTION"))?;
+        }
+        let result = f(self);
+        if owned {
+            match result {
+                Ok(_) => {
+                    self.run_on_connection(cstr!("COMMIT"))?;
+                }
+                Err(_) => {
+                    let _ = self.run_on_connection(cstr!("ROLLBACK"));
+                }
+            }
+        }
+        result
+    }
+    /// Whether a transaction is open on the connection: only then do consecutive statements
+    /// share a transaction id
+    fn in_transaction(&self) -> Result<bool, PreparedStatementError> {
+        let id = || {
+            self.run_on_connection(cstr!("SELECT txid_current()"))?
+                .get::<i64>(0, 0)
+                .map_err(|e| PreparedStatementError::TransactionError(e.to_string()))
+        };
+        Ok(id()? == id()?)
+    }
+    fn run_on_connection(&self, sql: &CStr) -> Result<QueryResult, PreparedStatementError> {
+        let connection = self.handle.connection();
+        unsafe {
+            let mut result: ffi::duckdb_result = std::mem::zeroed();
+            connection.begin_query();
+            let r = ffi::duckdb_query(***connection, sql.as_ptr(), &mut result);
+            let h = QueryResultHandle::from_raw_connection(result, connection.clone());
+            if r != ffi::DuckDBSuccess {
+                return Err(PreparedStatementError::TransactionError(
+                    h.error().unwrap_or_default(),
+                ));
+            }
+            Ok(QueryResult::from(h))
+        }
+    }
+    pub fn execute(&self) -> Result<ArrowResult, PreparedStatementError> {
+        let span = span!("execute", sql = redact_literals(self.handle.query()));
+        let result = span.in_scope(|| unsafe {
+            let mut result: ffi::duckdb_arrow = std::mem::zeroed();
+            self.handle.connection().begin_query();
+            let r = ffi::duckdb_execute_prepared_arrow(**self, &mut result);
+            let h: ArrowResult =
+                ArrowResultHandle::from_raw_statement(result, self.handle.clone()).into();
+            if r != ffi::DuckDBSuccess {
+                return Err(PreparedStatementError::ExecuteError(h.error()));
+            }
+            Ok(h)
+        });
+        if let Ok(result) = &result {
+            span.record_rows(result.row_count());
+        }
+        span.finish(result)
+    }
+    /// Execute and return a result readable without Arrow
+    pub fn execute_result(&self) -> Result<QueryResult, PreparedStatementError> {
+        let span = span!("execute", sql = redact_literals(self.handle.query()));
+        let result = span.in_scope(|| unsafe {
+            let mut result: ffi::duckdb_result = std::mem::zeroed();
+            self.handle.connection().begin_query();
+            let r = ffi::duckdb_execute_prepared(**self, &mut result);
+            let h = QueryResultHandle::from_raw_statement(result, self.handle.clone());
+            if r != ffi::DuckDBSuccess {
+                return Err(PreparedStatementError::ExecuteError(
+                    h.error().unwrap_or_default(),
+                ));
+            }
+            Ok(QueryResult::from(h))
+        });
+        if let Ok(result) = &result {
+            span.record_rows(result.row_count());
+        }
+        span.finish(result)
+    }
+    /// Execute without materializing the result. Chunks are fetched as the result is read.
+    pub fn execute_streaming(&self) -> Result<StreamingResult, PreparedStatementError> {
+        let span = span!("execute", sql = redact_literals(self.handle.query()));
+        let result = span.in_scope(|| unsafe {
+            let mut pending: ffi::duckdb_pending_result = std::ptr::null_mut();
+            self.handle.connection().begin_query();
+            let r = ffi::duckdb_pending_prepared_streaming(**self, &mut pending);
+            let pending = PendingResultHandle::from_raw(pending);
+            if r != ffi::DuckDBSuccess {
+                return Err(PreparedStatementError::ExecuteError(
+                    pending.error().unwrap_or_default(),
+                ));
+            }
+            let mut result: ffi::duckdb_result = std::mem::zeroed();
+            let r = ffi::duckdb_execute_pending(*pending, &mut result);
+            let h = QueryResultHandle::from_raw_statement(result, self.handle.clone());
+            if r != ffi::DuckDBSuccess {
+                return Err(PreparedStatementError::ExecuteError(
+                    h.error().unwrap_or_default(),
+                ));
+            }
+            Ok(StreamingResult::from(h))
+        });
+        span.finish(result)
+    }
+}
+
+impl Deref for PreparedStatement {
+    type Target = ffi::duckdb_prepared_statement;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+impl From<Arc<PreparedStatementHandle>> for PreparedStatement {
+    fn from(value: Arc<PreparedStatementHandle>) -> Self {
+        Self {
+            handle: value,
+            current_index: 1,
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::Arc,
+    };
+
+    use ::arrow::{
+        array::{Int32Array, StringArray},
+        datatypes::{Field, Schema},
+        record_batch::RecordBatch,
+    };
+
+    use crate::{database::Database, error::QuackError};
+
+    use super::*;
+
+    #[test]
+    fn test_execute_many() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER PRIMARY KEY, v VARCHAR)")?;
+        let mut insert = conn.prepare("INSERT INTO t VALUES (?, ?)")?;
+        let changed = insert.execute_many((0..100).map(|i| (i, format!("v{i}"))))?;
+        assert_eq!(changed, 100);
+
+        let mut update = conn.prepare("UPDATE t SET v = v || '!' WHERE id = $id")?;
+        let changed = update.execute_many((0..10).map(|i| Named([("id", i)])))?;
+        assert_eq!(changed, 10);
+
+        // The duplicate key rolls back the whole batch
+        match insert.execute_many([(100, "a"), (101, "b"), (0, "c")]) {
+            Err(PreparedStatementError::RowError { row: 2, .. }) => {}
+            other => panic!("expected failure at row 2, got {other:?}"),
+        }
+        let count = conn.query_result("SELECT count(*) FROM t")?;
+        assert_eq!(count.get::<i64>(0, 0)?, Some(100));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_many_in_transaction() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER PRIMARY KEY)")?;
+        conn.query("BEGIN TRANSACTION")?;
+        conn.query("INSERT INTO t VALUES (-1)")?;
+        let mut insert = conn.prepare("INSERT INTO t VALUES (?)")?;
+        assert_eq!(insert.execute_many((0..10).map(|i| (i,)))?, 10);
+        // The batch joined the outer transaction, which is still open
+        let other = db.connect()?;
+        let count = other.query_result("SELECT count(*) FROM t")?;
+        assert_eq!(count.get::<i64>(0, 0)?, Some(0));
+        conn.query("COMMIT")?;
+        let count = other.query_result("SELECT count(*) FROM t")?;
+        assert_eq!(count.get::<i64>(0, 0)?, Some(11));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_batch_arrow() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t(id INTEGER, v VARCHAR)")?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("v", DataType::Utf8, true),
+            Field::new("id", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+            ],
+        )?;
+        // By name, regardless of column order
+        let mut insert = conn.prepare("INSERT INTO t VALUES ($id, $v)")?;
+        assert_eq!(insert.execute_batch_arrow(&batch)?, 3);
+        // By position
+        let mut insert = conn.prepare("INSERT INTO t VALUES (?2, ?1)")?;
+        assert_eq!(insert.execute_batch_arrow(&batch)?, 3);
+        let result = conn.query_result("SELECT count(*), count(v), sum(id) FROM t")?;
+        assert_eq!(result.get::<i64>(0, 0)?, Some(6));
+        assert_eq!(result.get::<i64>(1, 0)?, Some(4));
+        Ok(())
+    }
+
+    struct Point {
+        x: i64,
+        label: String,
+    }
+
+    impl ToStruct for Point {
+        const FIELD_NAMES: &'static [&'static str] = &["x", "label"];
+        type Fields = (i64, String);
+        fn to_fields(&self) -> Self::Fields {
+            (self.x, self.label.clone())
+        }
+    }
+
+    #[test]
+    fn test_nested_params() -> Result<(), QuackError> {
+        let db = Database::open(None)?;
+        let conn = db.connect()?;
+        conn.query("CREATE TABLE t AS SELECT range::INTEGER AS id FROM range(100)")?;
+
+        let mut any = conn.prepare("SELECT count(*) FROM t WHERE id = ANY(?)")?;
+        any.bind(vec![1, 5, 7, 500])?;
+        assert_eq!(any.execute_result()?.get::<i64>(0, 0)?, Some(3));
+        any.clear_bindings()?;
+        any.reset().bind(Vec::<i32>::new())?;
+        assert_eq!(any.execute_result()?.get::<i64>(0, 0)?, Some(0));
+
+        let mut text = conn.prepare("SELECT ?::VARCHAR")?;
+        let read = |stmt: &PreparedStatement| -> Result<Option<String>, QuackError> {
+            Ok(stmt.execute_result()?.get_string(0, 0)?)
+        };
+        text.bind(&["a", "b"][..])?;
+        assert_eq!(read(&text)?.as_deref(), Some("[a, b]"));
+        text.clear_bindings()?;
+        text.reset().bind(vec![vec![1], vec![2, 3]])?;
+        assert_eq!(read(&text)?.as_deref(), Some("[[1], [2, 3]]"));
+        text.clear_bindings()?;
+        text.reset().bind(Struct(Point {
+            x: 1,
+            label: "one".to_owned(),
+        }))?;
+        assert_eq!(read(&text)?.as_deref(), Some("{'x': 1, 'label': one}"));
+
+        let mut map = conn.prepare("SELECT map_from_entries(?)::VARCHAR")?;
+        map.bind(BTreeMap::from([("a", 1), ("b", 2)]))?;
+        assert_eq!(
+            map.execute_result()?.get_string(0, 0)?.as_deref(),
+            Some("{a=1, b=2}")
+        );
+        map.clear_bindings()?;
+        map.reset()
+            .bind(HashMap::from([("k".to_owned(), vec![1, 2])]))?;
+        assert_eq!(
+            map.execute_result()?.get_string(0, 0)?.as_deref(),
+            Some("{k=[1, 2]}")
+        );
+        Ok(())
+    }
+}
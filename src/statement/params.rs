@@ -0,0 +1,113 @@
+use quackdb_internal::conversion::BindParam;
+
+use super::{PreparedStatement, PreparedStatementError};
+
+/// A full set of parameters for one execution of a prepared statement.
+///
+/// Tuples of up to 16 [`BindParam`] values, arrays, slices and `Vec`s bind by position, and
+/// [`Named`] binds by name. Use [`params!`](crate::params) for a list of mixed types.
+pub trait Params {
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError>;
+}
+
+impl Params for () {
+    fn bind_to(self, _statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        Ok(())
+    }
+}
+
+impl<T: BindParam> Params for &[T] {
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        for (i, param) in self.iter().enumerate() {
+            statement.bind_at(param, i as u64 + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BindParam, const N: usize> Params for [T; N] {
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        self.as_slice().bind_to(statement)
+    }
+}
+
+impl<T: BindParam, const N: usize> Params for &[T; N] {
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        self.as_slice().bind_to(statement)
+    }
+}
+
+impl<T: BindParam> Params for Vec<T> {
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        self.as_slice().bind_to(statement)
+    }
+}
+
+impl<T: BindParam> Params for &Vec<T> {
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        self.as_slice().bind_to(statement)
+    }
+}
+
+/// Positional parameters of mixed types, borrowed rather than moved.
+///
+/// ```no_run
+/// # use quackdb::{database::Database, params};
+/// # let db = Database::open(None).unwrap();
+/// # let conn = db.connect().unwrap();
+/// let name = String::from("duck");
+/// conn.execute("INSERT INTO t VALUES (?, ?, ?)", params![1, name, None::<f64>])
+///     .unwrap();
+/// ```
+#[macro_export]
+macro_rules! params {
+    ($($param:expr),* $(,)?) => {
+        &[$(&$param as &dyn $crate::statement::BindParam),*] as &[&dyn $crate::statement::BindParam]
+    };
+}
+
+/// Parameters bound by name, e.g. `Named([("id", 1), ("owner", 2)])` for `$id` and `$owner`
+#[derive(Debug, Clone)]
+pub struct Named<I>(pub I);
+
+impl<I, K, T> Params for Named<I>
+where
+    I: IntoIterator<Item = (K, T)>,
+    K: AsRef<str>,
+    T: BindParam,
+{
+    fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+        for (name, value) in self.0 {
+            statement.bind_named(name.as_ref(), value)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_params_for_tuple {
+    ($($idx:tt $ty:ident),+) => {
+        impl<$($ty: BindParam),+> Params for ($($ty,)+) {
+            fn bind_to(self, statement: &mut PreparedStatement) -> Result<(), PreparedStatementError> {
+                $(statement.bind_at(self.$idx, $idx + 1)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_params_for_tuple! {0 T0}
+impl_params_for_tuple! {0 T0, 1 T1}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14}
+impl_params_for_tuple! {0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14, 15 T15}
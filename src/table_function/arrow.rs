@@ -0,0 +1,127 @@
+use std::{
+    ffi::CString,
+    sync::{Mutex, PoisonError},
+};
+
+use arrow::{
+    datatypes::SchemaRef,
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+
+use crate::{
+    data_chunk::{vector_size, DataChunk},
+    types::LogicalType,
+};
+
+use super::{BindInfo, InitInfo, Parameters, TableFunctionError};
+
+type BoxedReader = Box<dyn RecordBatchReader + Send>;
+
+/// Bind data of a table function backed by a [`RecordBatchReader`]
+pub(crate) struct ArrowBindData {
+    parameters: Parameters,
+    schema: SchemaRef,
+    /// Reader created during bind, used by the first scan
+    reader: Mutex<Option<BoxedReader>>,
+}
+
+/// Scan state of a table function backed by a [`RecordBatchReader`]
+pub(crate) struct ArrowScan {
+    reader: BoxedReader,
+    batch: Option<RecordBatch>,
+    offset: usize,
+}
+
+pub(crate) fn arrow_bind<F, E>(
+    info: &BindInfo,
+    make_reader: &F,
+) -> Result<ArrowBindData, TableFunctionError<E>>
+where
+    F: Fn(&Parameters) -> Result<BoxedReader, E>,
+{
+    let parameters = info.parameters();
+    let reader = make_reader(&parameters)?;
+    let schema = reader.schema();
+    for field in schema.fields() {
+        let type_ = LogicalType::from_arrow(field.data_type())
+            .map_err(TableFunctionError::LogicalTypeError)?;
+        let name = CString::new(field.name().as_str())
+            .map_err(|_| TableFunctionError::BadColumnName(field.name().clone()))?;
+        info.add_result_column(&name, &type_);
+    }
+    Ok(ArrowBindData {
+        parameters,
+        schema,
+        reader: Mutex::new(Some(reader)),
+    })
+}
+
+pub(crate) fn arrow_init<F, E>(
+    info: &InitInfo,
+    bind: &ArrowBindData,
+    make_reader: &F,
+) -> Result<Mutex<ArrowScan>, TableFunctionError<E>>
+where
+    F: Fn(&Parameters) -> Result<BoxedReader, E>,
+{
+    info.set_max_threads(1);
+    let reader = bind
+        .reader
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    // A prepared statement is bound once but may be scanned many times
+    let reader = match reader {
+        Some(reader) => reader,
+        None => make_reader(&bind.parameters)?,
+    };
+    if reader.schema() != bind.schema {
+        return Err(TableFunctionError::ArrowError(ArrowError::SchemaError(
+            "record batch reader schema changed since bind".to_owned(),
+        )));
+    }
+    Ok(Mutex::new(ArrowScan {
+        reader,
+        batch: None,
+        offset: 0,
+    }))
+}
+
+/// Copy up to one vector of rows into `chunk`, splitting batches larger than the vector size
+pub(crate) fn arrow_scan<E>(
+    chunk: &DataChunk,
+    scan: &Mutex<ArrowScan>,
+) -> Result<(), TableFunctionError<E>> {
+    let mut scan = scan.lock().unwrap_or_else(PoisonError::into_inner);
+    let scan = &mut *scan;
+    loop {
+        if let Some(batch) = &scan.batch {
+            if scan.offset < batch.num_rows() {
+                break;
+            }
+        }
+        match scan.reader.next() {
+            Some(batch) => {
+                scan.batch = Some(batch.map_err(TableFunctionError::ArrowError)?);
+                scan.offset = 0;
+            }
+            None => {
+                chunk.set_size(0);
+                return Ok(());
+            }
+        }
+    }
+    let batch = scan.batch.as_ref().expect("batch is loaded");
+    let len = (batch.num_rows() - scan.offset).min(vector_size() as usize);
+    let slice = batch.slice(scan.offset, len);
+    for (i, column) in slice.columns().iter().enumerate() {
+        let vector = chunk
+            .vector(i as u64)
+            .expect("chunk has one vector per schema field");
+        unsafe { vector.write_arrow(column.as_ref()) }.map_err(TableFunctionError::ArrowError)?;
+    }
+    chunk.set_size(len as u64);
+    scan.offset += len;
+    Ok(())
+}
@@ -1,22 +1,42 @@
-use std::{ffi::CStr, ops::Deref};
+use std::{cell::RefCell, ffi::CStr, ops::Deref, sync::Arc};
 
-use quackdb_internal::ffi;
+use quackdb_internal::{ffi, handles::ValueHandle};
 
 use crate::types::LogicalType;
 
+use super::{Parameters, Projection, ResultColumn};
+
 pub struct BindInfo {
     handle: ffi::duckdb_bind_info,
+    columns: RefCell<Vec<ResultColumn>>,
 }
 
 impl From<ffi::duckdb_bind_info> for BindInfo {
     fn from(handle: ffi::duckdb_bind_info) -> Self {
-        Self { handle }
+        Self {
+            handle,
+            columns: RefCell::default(),
+        }
     }
 }
 
 impl BindInfo {
     pub fn add_result_column(&self, name: &CStr, type_: &LogicalType) {
         unsafe { ffi::duckdb_bind_add_result_column(**self, name.as_ptr(), **type_) }
+        let mut columns = self.columns.borrow_mut();
+        let index = columns.len();
+        columns.push(ResultColumn {
+            index,
+            name: name.to_string_lossy().into_owned(),
+            type_id: type_.type_id(),
+        });
+    }
+    /// Result columns added so far
+    pub fn result_columns(&self) -> Vec<ResultColumn> {
+        self.columns.borrow().clone()
+    }
+    pub(crate) fn into_result_columns(self) -> Arc<[ResultColumn]> {
+        self.columns.into_inner().into()
     }
     pub fn parameter_count(&self) -> u64 {
         unsafe { ffi::duckdb_bind_get_parameter_count(**self) }
@@ -24,6 +44,13 @@ impl BindInfo {
     pub fn set_cardinality(&self, cardinality: u64, is_exact: bool) {
         unsafe { ffi::duckdb_bind_set_cardinality(**self, cardinality, is_exact) }
     }
+    /// All positional parameters
+    pub fn parameters(&self) -> Parameters {
+        (0..self.parameter_count())
+            .map(|i| unsafe { ValueHandle::from_raw(self.parameter(i)) }.into())
+            .collect::<Vec<_>>()
+            .into()
+    }
     /// # Safety
     /// * Index must be in range
     /// * Result must be destroyed
@@ -48,15 +75,17 @@ impl Deref for BindInfo {
 
 pub struct InitInfo {
     handle: ffi::duckdb_init_info,
-}
-
-impl From<ffi::duckdb_init_info> for InitInfo {
-    fn from(handle: ffi::duckdb_init_info) -> Self {
-        Self { handle }
-    }
+    projection: Arc<Projection>,
 }
 
 impl InitInfo {
+    pub(crate) fn new(handle: ffi::duckdb_init_info, projection: Arc<Projection>) -> Self {
+        Self { handle, projection }
+    }
+    /// Columns requested by DuckDB, mapped to the bind-time result columns
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
     pub fn column_count(&self) -> u64 {
         unsafe { ffi::duckdb_init_get_column_count(**self) }
     }
@@ -78,11 +107,16 @@ impl Deref for InitInfo {
 
 pub struct FunctionInfo {
     pub handle: ffi::duckdb_function_info,
+    projection: Arc<Projection>,
 }
 
-impl From<ffi::duckdb_function_info> for FunctionInfo {
-    fn from(handle: ffi::duckdb_function_info) -> Self {
-        Self { handle }
+impl FunctionInfo {
+    pub(crate) fn new(handle: ffi::duckdb_function_info, projection: Arc<Projection>) -> Self {
+        Self { handle, projection }
+    }
+    /// Columns of the output data chunk, mapped to the bind-time result columns
+    pub fn projection(&self) -> &Projection {
+        &self.projection
     }
 }
 
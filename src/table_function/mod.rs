@@ -1,15 +1,36 @@
+mod arrow;
+pub(crate) use arrow::{arrow_bind, arrow_init, arrow_scan, ArrowBindData, ArrowScan};
 mod info;
 pub use info::*;
+mod parameters;
+pub use parameters::*;
+mod parallel;
+pub use parallel::ParallelTableFunction;
+pub(crate) use parallel::{parallel_scan, LocalScan, WorkQueue};
+mod projection;
+pub use projection::*;
 
+use std::sync::Arc;
+
+use ::arrow::error::ArrowError;
 use quackdb_internal::ffi;
 use thiserror::Error;
 
+use crate::types::LogicalTypeError;
+
 #[derive(Error, Debug)]
 pub enum TableFunctionError<E> {
     #[error(transparent)]
     UserError(#[from] E),
+    #[error(transparent)]
+    ArrowError(ArrowError),
+    #[error(transparent)]
+    LogicalTypeError(LogicalTypeError),
+    #[error("bad column name: {0}")]
+    BadColumnName(String),
 }
 
+#[allow(clippy::type_complexity)]
 pub(crate) struct ExtraInfo<B, I, LI, D, E> {
     pub bind: Box<dyn Fn(&BindInfo, &D) -> Result<B, E> + Send>,
     pub init: Box<dyn Fn(&InitInfo, &B, &D) -> Result<I, E> + Send>,
@@ -21,3 +42,15 @@ pub(crate) struct ExtraInfo<B, I, LI, D, E> {
     >,
     pub extra: D,
 }
+
+/// Bind data stored by DuckDB, along with the columns declared during bind
+pub(crate) struct BindData<B> {
+    pub data: B,
+    pub columns: Arc<[ResultColumn]>,
+}
+
+/// Global init data stored by DuckDB, along with the projection of the scan
+pub(crate) struct InitData<I> {
+    pub data: I,
+    pub projection: Arc<Projection>,
+}
@@ -0,0 +1,120 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex, PoisonError,
+};
+
+use crate::{data_chunk::DataChunk, types::LogicalType};
+
+use super::{BindInfo, FunctionInfo, InitInfo};
+
+/// A table function whose scan is split into partitions, e.g. files or row ranges.
+///
+/// Global init produces the list of partitions. Each DuckDB thread then claims partitions
+/// one at a time and scans them until no partition is left.
+pub trait ParallelTableFunction: Send + Sync + 'static {
+    type BindData: Send + Sync;
+    /// A unit of work claimed by one thread
+    type Partition: Send + Sync;
+    /// State of a thread while scanning one partition
+    type ScanState: Send;
+    type Error: std::error::Error + Send;
+
+    fn bind(&self, info: &BindInfo) -> Result<Self::BindData, Self::Error>;
+    /// Split the scan into partitions
+    fn partitions(
+        &self,
+        info: &InitInfo,
+        bind: &Self::BindData,
+    ) -> Result<Vec<Self::Partition>, Self::Error>;
+    /// Start scanning a claimed partition
+    fn open(
+        &self,
+        bind: &Self::BindData,
+        partition: &Self::Partition,
+    ) -> Result<Self::ScanState, Self::Error>;
+    /// Fill `chunk` with the next rows of the partition and return the number of rows written.
+    /// Returning `0` marks the partition as exhausted.
+    ///
+    /// The chunk size is set from the return value.
+    fn scan(
+        &self,
+        info: &FunctionInfo,
+        chunk: &DataChunk,
+        bind: &Self::BindData,
+        partition: &Self::Partition,
+        state: &mut Self::ScanState,
+    ) -> Result<u64, Self::Error>;
+    /// Types of positional parameters
+    fn parameters(&self) -> Vec<LogicalType> {
+        Vec::new()
+    }
+    /// Maximum number of threads scanning `partition_count` partitions
+    fn max_threads(&self, partition_count: usize) -> u64 {
+        partition_count.max(1) as u64
+    }
+    fn supports_projection(&self) -> bool {
+        false
+    }
+}
+
+/// Partitions shared by all threads of a scan
+pub(crate) struct WorkQueue<P> {
+    partitions: Vec<P>,
+    next: AtomicUsize,
+}
+
+impl<P> WorkQueue<P> {
+    pub fn new(partitions: Vec<P>) -> Self {
+        Self {
+            partitions,
+            next: AtomicUsize::new(0),
+        }
+    }
+    /// Claim the next unclaimed partition
+    fn claim(&self) -> Option<usize> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        (index < self.partitions.len()).then_some(index)
+    }
+}
+
+/// Partition currently scanned by one thread
+pub(crate) struct LocalScan<S> {
+    current: Mutex<Option<(usize, S)>>,
+}
+
+impl<S> Default for LocalScan<S> {
+    fn default() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+}
+
+pub(crate) fn parallel_scan<T: ParallelTableFunction>(
+    function: &T,
+    info: &FunctionInfo,
+    chunk: &DataChunk,
+    bind: &T::BindData,
+    queue: &WorkQueue<T::Partition>,
+    local: &LocalScan<T::ScanState>,
+) -> Result<(), T::Error> {
+    let mut current = local.current.lock().unwrap_or_else(PoisonError::into_inner);
+    loop {
+        if current.is_none() {
+            let Some(index) = queue.claim() else {
+                // Queue exhausted: an empty chunk ends the scan of this thread
+                chunk.set_size(0);
+                return Ok(());
+            };
+            let state = function.open(bind, &queue.partitions[index])?;
+            *current = Some((index, state));
+        }
+        let (index, state) = current.as_mut().expect("a partition is claimed");
+        let rows = function.scan(info, chunk, bind, &queue.partitions[*index], state)?;
+        if rows > 0 {
+            chunk.set_size(rows);
+            return Ok(());
+        }
+        *current = None;
+    }
+}
@@ -0,0 +1,38 @@
+use std::ops::Index;
+
+use crate::value::Value;
+
+/// Positional parameters of a table function call
+#[derive(Debug, Default)]
+pub struct Parameters {
+    values: Vec<Value>,
+}
+
+impl From<Vec<Value>> for Parameters {
+    fn from(values: Vec<Value>) -> Self {
+        Self { values }
+    }
+}
+
+impl Parameters {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.values.get(index)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.values.iter()
+    }
+}
+
+impl Index<usize> for Parameters {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use quackdb_internal::{ffi, type_id::TypeId};
+
+/// A result column declared with [`BindInfo::add_result_column`](super::BindInfo::add_result_column)
+#[derive(Debug, Clone)]
+pub struct ResultColumn {
+    /// Position in the order of declaration
+    pub index: usize,
+    pub name: String,
+    pub type_id: Option<TypeId>,
+}
+
+/// Mapping between the columns of output data chunks and the bind-time result columns.
+///
+/// With projection pushdown enabled, DuckDB only asks for the columns a query uses, so the
+/// `n`-th column of the output chunk is not necessarily the `n`-th declared column.
+#[derive(Debug, Clone)]
+pub struct Projection {
+    columns: Arc<[ResultColumn]>,
+    indices: Vec<u64>,
+}
+
+impl Projection {
+    /// # Safety
+    /// * `info` must be a valid init info
+    pub(crate) unsafe fn from_init_info(
+        info: ffi::duckdb_init_info,
+        columns: Arc<[ResultColumn]>,
+    ) -> Self {
+        let count = ffi::duckdb_init_get_column_count(info);
+        let indices = (0..count)
+            .map(|i| ffi::duckdb_init_get_column_index(info, i))
+            .collect();
+        Self { columns, indices }
+    }
+    /// Number of columns in the output chunk
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+    /// All columns declared at bind time, projected or not
+    pub fn result_columns(&self) -> &[ResultColumn] {
+        &self.columns
+    }
+    /// Declared column index of an output chunk column.
+    /// `None` for special columns such as the row id.
+    pub fn column_index(&self, output_index: usize) -> Option<usize> {
+        let index = usize::try_from(*self.indices.get(output_index)?).ok()?;
+        (index < self.columns.len()).then_some(index)
+    }
+    /// Declared column of an output chunk column
+    pub fn column(&self, output_index: usize) -> Option<&ResultColumn> {
+        self.column_index(output_index).map(|i| &self.columns[i])
+    }
+    /// Output chunk position of a declared column, or `None` if it is not projected
+    pub fn output_index(&self, column_index: usize) -> Option<usize> {
+        self.indices.iter().position(|&i| i == column_index as u64)
+    }
+    /// Output chunk position of the declared column with `name`, or `None` if it is not projected
+    pub fn output_index_by_name(&self, name: &str) -> Option<usize> {
+        let column = self.columns.iter().find(|c| c.name == name)?;
+        self.output_index(column.index)
+    }
+    pub fn is_projected(&self, column_index: usize) -> bool {
+        self.output_index(column_index).is_some()
+    }
+    /// Iterate over `(output_index, column)` of projected columns
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &ResultColumn)> {
+        (0..self.len()).filter_map(|i| self.column(i).map(|c| (i, c)))
+    }
+}
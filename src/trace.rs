@@ -0,0 +1,166 @@
+//! Spans recorded with the `tracing` feature. Without the feature, spans are zero sized
+//! and every method is a no-op.
+
+use std::fmt::Display;
+
+#[cfg(feature = "tracing")]
+pub(crate) struct Span {
+    span: tracing::Span,
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl Span {
+    pub(crate) fn new(span: tracing::Span) -> Self {
+        Self {
+            span,
+            start: std::time::Instant::now(),
+        }
+    }
+    pub(crate) fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.span.in_scope(f)
+    }
+    pub(crate) fn record_rows(&self, rows: u64) {
+        self.span.record("rows", rows);
+    }
+    /// Record the duration and any error, and pass the result through
+    pub(crate) fn finish<T, E: Display>(self, result: Result<T, E>) -> Result<T, E> {
+        let elapsed = self.start.elapsed().as_micros() as u64;
+        self.span.record("duration_us", elapsed);
+        if let Err(e) = &result {
+            self.span.record("error", tracing::field::display(e));
+        }
+        result
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    pub(crate) fn in_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+    pub(crate) fn record_rows(&self, _rows: u64) {}
+    pub(crate) fn finish<T, E: Display>(self, result: Result<T, E>) -> Result<T, E> {
+        result
+    }
+}
+
+/// Open a span named `quackdb.<name>` with the given display fields, plus `rows`,
+/// `duration_us` and `error` recorded later. Field values are only evaluated with the
+/// `tracing` feature.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        $crate::trace::Span::new(::tracing::debug_span!(
+            target: "quackdb",
+            concat!("quackdb.", $name),
+            $($field = %$value,)*
+            rows = ::tracing::field::Empty,
+            duration_us = ::tracing::field::Empty,
+            error = ::tracing::field::Empty,
+        ))
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {{
+        // Type check the fields without evaluating them
+        let _ = || {
+            $(let _ = &$value;)*
+        };
+        $crate::trace::Span
+    }};
+}
+
+pub(crate) use span;
+
+#[cfg(all(test, feature = "tracing"))]
+mod test {
+    use std::{
+        fmt::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+    use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+    use crate::{database::Database, error::QuackError};
+
+    /// A span with its name and fields, as `name=value` pairs
+    type CapturedSpan = (Id, &'static str, String);
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<CapturedSpan>>>);
+
+    struct Fields<'a>(&'a mut String);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            let _ = write!(self.0, "{}={:?} ", field.name(), value);
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = String::new();
+            attrs.record(&mut Fields(&mut fields));
+            let mut spans = self.0.lock().unwrap();
+            spans.push((id.clone(), attrs.metadata().name(), fields));
+        }
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            let mut spans = self.0.lock().unwrap();
+            if let Some((_, _, fields)) = spans.iter_mut().rev().find(|(i, _, _)| i == id) {
+                values.record(&mut Fields(fields));
+            }
+        }
+    }
+
+    impl Capture {
+        fn find(&self, name: &str, field: &str) -> Option<String> {
+            let spans = self.0.lock().unwrap();
+            spans
+                .iter()
+                .find(|(_, n, fields)| *n == name && fields.contains(field))
+                .map(|(_, _, fields)| fields.clone())
+        }
+    }
+
+    #[test]
+    fn test_spans() -> Result<(), QuackError> {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || -> Result<(), QuackError> {
+            let db = Database::open(None)?;
+            let conn = db.connect()?;
+            conn.query("CREATE TABLE t(x INTEGER)")?;
+            conn.query("SELECT 'secret', 42")?;
+            conn.prepare("SELECT * FROM t WHERE x = 7")?;
+            assert!(conn.query("SELECT * FROM missing").is_err());
+            let mut appender = conn.appender(None, "t")?;
+            appender.append_row((1,))?;
+            drop(appender);
+            Ok(())
+        })?;
+
+        let query = capture.find("quackdb.query", "SELECT ?, ?").unwrap();
+        assert!(query.contains("duration_us="));
+        assert!(!query.contains("secret"));
+        assert!(capture
+            .find("quackdb.prepare", "SELECT * FROM t WHERE x = ?")
+            .is_some());
+        let failed = capture.find("quackdb.query", "missing").unwrap();
+        assert!(failed.contains("error="));
+        assert!(capture
+            .find("quackdb.appender_flush", "implicit=true")
+            .is_some());
+        Ok(())
+    }
+}
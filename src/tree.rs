@@ -0,0 +1,12 @@
+//! Walking the operator trees of query plans and profiles.
+
+/// `root` followed by every node below it, each node before its children
+pub(crate) fn depth_first<'a, T>(root: &'a T, children: fn(&'a T) -> &'a [T]) -> Vec<&'a T> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        stack.extend(children(node).iter().rev());
+    }
+    nodes
+}
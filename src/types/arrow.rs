@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use arrow::datatypes::{
+    DataType, Field, Fields, IntervalUnit, Schema, TimeUnit, UnionFields, UnionMode,
+};
+use quackdb_internal::type_id::TypeId;
+
+use super::{LogicalType, LogicalTypeError};
+
+/// Field metadata key naming an Arrow extension type
+const EXTENSION_TYPE_NAME_KEY: &str = "ARROW:extension:name";
+/// Extension name of Arrow UUID fields
+const UUID_EXTENSION: &str = "arrow.uuid";
+
+impl LogicalType {
+    /// DuckDB type corresponding to an Arrow type.
+    ///
+    /// Dictionaries map to their value type, since the members of an `ENUM` are not part of
+    /// the Arrow type.
+    pub fn from_arrow(data_type: &DataType) -> Result<Self, LogicalTypeError> {
+        let id = match data_type {
+            DataType::Boolean => TypeId::Boolean,
+            DataType::Int8 => TypeId::TinyInt,
+            DataType::Int16 => TypeId::SmallInt,
+            DataType::Int32 => TypeId::Integer,
+            DataType::Int64 => TypeId::BigInt,
+            DataType::UInt8 => TypeId::UTinyInt,
+            DataType::UInt16 => TypeId::USmallInt,
+            DataType::UInt32 => TypeId::UInteger,
+            DataType::UInt64 => TypeId::UBigInt,
+            DataType::Float32 => TypeId::Float,
+            DataType::Float64 => TypeId::Double,
+            DataType::Utf8 | DataType::LargeUtf8 => TypeId::VarChar,
+            DataType::Binary | DataType::LargeBinary => TypeId::Blob,
+            DataType::Date32 => TypeId::Date,
+            DataType::Time64(TimeUnit::Microsecond) => TypeId::Time,
+            DataType::Timestamp(TimeUnit::Second, _) => TypeId::TimestampS,
+            DataType::Timestamp(TimeUnit::Millisecond, _) => TypeId::TimestampMs,
+            DataType::Timestamp(TimeUnit::Microsecond, _) => TypeId::Timestamp,
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => TypeId::TimestampNs,
+            DataType::Interval(IntervalUnit::MonthDayNano) => TypeId::Interval,
+            &DataType::Decimal128(width, scale) if width <= 38 && scale >= 0 => {
+                return Ok(Self::decimal(width, scale as u8));
+            }
+            DataType::List(child)
+            | DataType::LargeList(child)
+            | DataType::FixedSizeList(child, _) => {
+                return Ok(Self::list(&Self::from_arrow_field(child)?));
+            }
+            DataType::Struct(fields) => {
+                let types = fields
+                    .iter()
+                    .map(|f| Self::from_arrow_field(f))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let members: Vec<(&str, &LogicalType)> = fields
+                    .iter()
+                    .zip(&types)
+                    .map(|(f, t)| (f.name().as_str(), t))
+                    .collect();
+                return Self::struct_type(&members);
+            }
+            DataType::Map(entries, _) => match entries.data_type() {
+                DataType::Struct(kv) if kv.len() == 2 => {
+                    let key = Self::from_arrow_field(&kv[0])?;
+                    let value = Self::from_arrow_field(&kv[1])?;
+                    return Ok(Self::map(&key, &value));
+                }
+                _ => return Err(LogicalTypeError::UnsupportedArrowType(data_type.clone())),
+            },
+            DataType::Dictionary(_, value) => return Self::from_arrow(value),
+            _ => return Err(LogicalTypeError::UnsupportedArrowType(data_type.clone())),
+        };
+        Self::try_from(id)
+    }
+
+    /// DuckDB type of an Arrow field, recognizing the `arrow.uuid` extension type
+    pub fn from_arrow_field(field: &Field) -> Result<Self, LogicalTypeError> {
+        let is_uuid = field
+            .metadata()
+            .get(EXTENSION_TYPE_NAME_KEY)
+            .is_some_and(|name| name == UUID_EXTENSION);
+        if is_uuid {
+            return Self::try_from(TypeId::Uuid);
+        }
+        Self::from_arrow(field.data_type())
+    }
+
+    /// Arrow type of this type, as produced by DuckDB's own Arrow export.
+    ///
+    /// `ENUM` becomes a dictionary of strings, while `UUID` and `HUGEINT` become `Utf8` and
+    /// `Decimal128(38, 0)` respectively.
+    pub fn to_arrow(&self) -> Result<DataType, LogicalTypeError> {
+        let id = self.type_id();
+        let data_type = match id.ok_or(LogicalTypeError::UnsupportedType(id))? {
+            TypeId::Boolean => DataType::Boolean,
+            TypeId::TinyInt => DataType::Int8,
+            TypeId::SmallInt => DataType::Int16,
+            TypeId::Integer => DataType::Int32,
+            TypeId::BigInt => DataType::Int64,
+            TypeId::UTinyInt => DataType::UInt8,
+            TypeId::USmallInt => DataType::UInt16,
+            TypeId::UInteger => DataType::UInt32,
+            TypeId::UBigInt => DataType::UInt64,
+            TypeId::Float => DataType::Float32,
+            TypeId::Double => DataType::Float64,
+            TypeId::HugeInt => DataType::Decimal128(38, 0),
+            TypeId::Decimal => {
+                DataType::Decimal128(self.decimal_width(), self.decimal_scale() as i8)
+            }
+            TypeId::Date => DataType::Date32,
+            TypeId::Time => DataType::Time64(TimeUnit::Microsecond),
+            TypeId::TimestampS => DataType::Timestamp(TimeUnit::Second, None),
+            TypeId::TimestampMs => DataType::Timestamp(TimeUnit::Millisecond, None),
+            TypeId::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+            TypeId::TimestampNs => DataType::Timestamp(TimeUnit::Nanosecond, None),
+            TypeId::Interval => DataType::Interval(IntervalUnit::MonthDayNano),
+            TypeId::VarChar | TypeId::Uuid => DataType::Utf8,
+            TypeId::Blob | TypeId::Bit => DataType::Binary,
+            TypeId::Enum => {
+                let key = match self.enum_internal_type_id() {
+                    Some(TypeId::UTinyInt) => DataType::UInt8,
+                    Some(TypeId::USmallInt) => DataType::UInt16,
+                    Some(TypeId::UInteger) => DataType::UInt32,
+                    other => return Err(LogicalTypeError::UnsupportedType(other)),
+                };
+                DataType::Dictionary(Box::new(key), Box::new(DataType::Utf8))
+            }
+            TypeId::List => {
+                let child = self.list_child_type().to_arrow()?;
+                DataType::List(Arc::new(Field::new("l", child, true)))
+            }
+            TypeId::Struct => {
+                let fields = self
+                    .struct_fields()
+                    .into_iter()
+                    .map(|(name, t)| Ok(Field::new(name, t.to_arrow()?, true)))
+                    .collect::<Result<Fields, LogicalTypeError>>()?;
+                DataType::Struct(fields)
+            }
+            TypeId::Map => {
+                let key = Field::new("key", self.map_key_type().to_arrow()?, false);
+                let value = Field::new("value", self.map_value_type().to_arrow()?, true);
+                let entries = DataType::Struct(Fields::from(vec![key, value]));
+                DataType::Map(Arc::new(Field::new("entries", entries, false)), false)
+            }
+            TypeId::Union => {
+                let members = self.union_members();
+                let fields = members
+                    .into_iter()
+                    .map(|(name, t)| Ok(Field::new(name, t.to_arrow()?, true)))
+                    .collect::<Result<Vec<_>, LogicalTypeError>>()?;
+                let type_ids = 0..fields.len() as i8;
+                DataType::Union(UnionFields::new(type_ids, fields), UnionMode::Sparse)
+            }
+            other => return Err(LogicalTypeError::UnsupportedType(Some(other))),
+        };
+        Ok(data_type)
+    }
+}
+
+/// Column names and types of an Arrow schema
+pub fn from_arrow_schema(schema: &Schema) -> Result<Vec<(String, LogicalType)>, LogicalTypeError> {
+    schema
+        .fields()
+        .iter()
+        .map(|f| Ok((f.name().clone(), LogicalType::from_arrow_field(f)?)))
+        .collect()
+}
+
+/// Arrow schema of named columns. All fields are nullable.
+pub fn to_arrow_schema<S: AsRef<str>>(
+    columns: &[(S, LogicalType)],
+) -> Result<Schema, LogicalTypeError> {
+    let fields = columns
+        .iter()
+        .map(|(name, t)| Ok(Field::new(name.as_ref(), t.to_arrow()?, true)))
+        .collect::<Result<Vec<_>, LogicalTypeError>>()?;
+    Ok(Schema::new(fields))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(data_type: DataType) {
+        let logical = LogicalType::from_arrow(&data_type).unwrap();
+        assert_eq!(logical.to_arrow().unwrap(), data_type);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(DataType::Int32);
+        round_trip(DataType::UInt64);
+        round_trip(DataType::Utf8);
+        round_trip(DataType::Binary);
+        round_trip(DataType::Decimal128(18, 3));
+        round_trip(DataType::Timestamp(TimeUnit::Nanosecond, None));
+        round_trip(DataType::Timestamp(TimeUnit::Second, None));
+        round_trip(DataType::Interval(IntervalUnit::MonthDayNano));
+        round_trip(DataType::List(Arc::new(Field::new(
+            "l",
+            DataType::Int64,
+            true,
+        ))));
+        round_trip(DataType::Struct(Fields::from(vec![
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Utf8, true),
+        ])));
+        let entries = DataType::Struct(Fields::from(vec![
+            Field::new("key", DataType::Utf8, false),
+            Field::new("value", DataType::Int32, true),
+        ]));
+        round_trip(DataType::Map(
+            Arc::new(Field::new("entries", entries, false)),
+            false,
+        ));
+    }
+
+    #[test]
+    fn test_enum_uuid() {
+        let e = LogicalType::enum_type(&["a", "b", "c"]).unwrap();
+        assert_eq!(e.enum_members(), ["a", "b", "c"]);
+        assert_eq!(
+            e.to_arrow().unwrap(),
+            DataType::Dictionary(Box::new(DataType::UInt8), Box::new(DataType::Utf8))
+        );
+        let uuid = Field::new("id", DataType::FixedSizeBinary(16), false).with_metadata(
+            [(
+                EXTENSION_TYPE_NAME_KEY.to_owned(),
+                UUID_EXTENSION.to_owned(),
+            )]
+            .into(),
+        );
+        let uuid = LogicalType::from_arrow_field(&uuid).unwrap();
+        assert_eq!(uuid.type_id(), Some(TypeId::Uuid));
+        assert_eq!(uuid.to_arrow().unwrap(), DataType::Utf8);
+    }
+
+    #[test]
+    fn test_schema() {
+        let columns = vec![
+            ("n", LogicalType::try_from(TypeId::BigInt).unwrap()),
+            ("d", LogicalType::decimal(10, 2)),
+        ];
+        let schema = to_arrow_schema(&columns).unwrap();
+        let back = from_arrow_schema(&schema).unwrap();
+        assert_eq!(back.len(), 2);
+        assert_eq!(back[0].0, "n");
+        assert_eq!(back[0].1.type_id(), Some(TypeId::BigInt));
+        assert_eq!(back[1].1.decimal_width(), 10);
+        assert_eq!(back[1].1.decimal_scale(), 2);
+    }
+}
@@ -1,5 +1,9 @@
-use std::ops::Deref;
+use std::{
+    ffi::{c_char, CStr, CString},
+    ops::Deref,
+};
 
+use arrow::datatypes::DataType;
 use quackdb_internal::{ffi, handles::LogicalTypeHandle, type_id::TypeId};
 use thiserror::Error;
 
@@ -12,6 +16,12 @@ pub struct LogicalType {
 pub enum LogicalTypeError {
     #[error("duckdb_create_logical_type() should not be used with DUCKDB_TYPE_DECIMAL")]
     DecimalError,
+    #[error("unsupported arrow type: {0}")]
+    UnsupportedArrowType(DataType),
+    #[error("unsupported duckdb type: {0:?}")]
+    UnsupportedType(Option<TypeId>),
+    #[error("bad member name: {0}")]
+    BadMemberName(String),
 }
 
 impl From<LogicalTypeHandle> for LogicalType {
@@ -31,6 +41,117 @@ impl TryFrom<TypeId> for LogicalType {
     }
 }
 
+impl LogicalType {
+    pub fn decimal(width: u8, scale: u8) -> Self {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_create_decimal_type(width, scale)) }.into()
+    }
+    pub fn list(child: &LogicalType) -> Self {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_create_list_type(**child)) }.into()
+    }
+    pub fn map(key: &LogicalType, value: &LogicalType) -> Self {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_create_map_type(**key, **value)) }.into()
+    }
+    pub fn struct_type(fields: &[(&str, &LogicalType)]) -> Result<Self, LogicalTypeError> {
+        let names = member_names(fields.iter().map(|(name, _)| *name))?;
+        let mut name_ptrs: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+        let mut types: Vec<ffi::duckdb_logical_type> = fields.iter().map(|(_, t)| ***t).collect();
+        let raw = unsafe {
+            ffi::duckdb_create_struct_type(
+                types.as_mut_ptr(),
+                name_ptrs.as_mut_ptr(),
+                fields.len() as u64,
+            )
+        };
+        Ok(unsafe { LogicalTypeHandle::from_raw(raw) }.into())
+    }
+    pub fn enum_type(members: &[&str]) -> Result<Self, LogicalTypeError> {
+        let names = member_names(members.iter().copied())?;
+        let mut name_ptrs: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+        let raw =
+            unsafe { ffi::duckdb_create_enum_type(name_ptrs.as_mut_ptr(), members.len() as u64) };
+        Ok(unsafe { LogicalTypeHandle::from_raw(raw) }.into())
+    }
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.handle.type_id()
+    }
+    /// Width of a `DECIMAL`
+    pub fn decimal_width(&self) -> u8 {
+        unsafe { ffi::duckdb_decimal_width(**self) }
+    }
+    /// Scale of a `DECIMAL`
+    pub fn decimal_scale(&self) -> u8 {
+        unsafe { ffi::duckdb_decimal_scale(**self) }
+    }
+    /// Child type of a `LIST`
+    pub fn list_child_type(&self) -> LogicalType {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_list_type_child_type(**self)) }.into()
+    }
+    /// Key type of a `MAP`
+    pub fn map_key_type(&self) -> LogicalType {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_map_type_key_type(**self)) }.into()
+    }
+    /// Value type of a `MAP`
+    pub fn map_value_type(&self) -> LogicalType {
+        unsafe { LogicalTypeHandle::from_raw(ffi::duckdb_map_type_value_type(**self)) }.into()
+    }
+    /// Field names and types of a `STRUCT`
+    pub fn struct_fields(&self) -> Vec<(String, LogicalType)> {
+        unsafe {
+            let count = ffi::duckdb_struct_type_child_count(**self);
+            (0..count)
+                .map(|i| {
+                    let name = take_string(ffi::duckdb_struct_type_child_name(**self, i));
+                    let raw = ffi::duckdb_struct_type_child_type(**self, i);
+                    (name, LogicalTypeHandle::from_raw(raw).into())
+                })
+                .collect()
+        }
+    }
+    /// Member names and types of a `UNION`
+    pub fn union_members(&self) -> Vec<(String, LogicalType)> {
+        unsafe {
+            let count = ffi::duckdb_union_type_member_count(**self);
+            (0..count)
+                .map(|i| {
+                    let name = take_string(ffi::duckdb_union_type_member_name(**self, i));
+                    let raw = ffi::duckdb_union_type_member_type(**self, i);
+                    (name, LogicalTypeHandle::from_raw(raw).into())
+                })
+                .collect()
+        }
+    }
+    /// Members of an `ENUM`
+    pub fn enum_members(&self) -> Vec<String> {
+        unsafe {
+            let count = ffi::duckdb_enum_dictionary_size(**self);
+            (0..count)
+                .map(|i| take_string(ffi::duckdb_enum_dictionary_value(**self, i as u64)))
+                .collect()
+        }
+    }
+    /// Physical type of the indices of an `ENUM`
+    pub fn enum_internal_type_id(&self) -> Option<TypeId> {
+        TypeId::from_repr(unsafe { ffi::duckdb_enum_internal_type(**self) })
+    }
+}
+
+fn member_names<'a>(
+    names: impl Iterator<Item = &'a str>,
+) -> Result<Vec<CString>, LogicalTypeError> {
+    names
+        .map(|name| {
+            CString::new(name).map_err(|_| LogicalTypeError::BadMemberName(name.to_owned()))
+        })
+        .collect()
+}
+
+/// Copy and free a string allocated by duckdb
+unsafe fn take_string(ptr: *mut c_char) -> String {
+    let s = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    ffi::duckdb_free(ptr.cast());
+    s
+}
+
 impl Deref for LogicalType {
     type Target = ffi::duckdb_logical_type;
 
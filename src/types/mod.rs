@@ -1,2 +1,4 @@
 mod logical_type;
 pub use logical_type::*;
+mod arrow;
+pub use self::arrow::{from_arrow_schema, to_arrow_schema};
@@ -0,0 +1,60 @@
+use std::{ffi::CStr, ops::Deref};
+
+use quackdb_internal::{ffi, handles::ValueHandle};
+
+/// A duckdb value, e.g. a table function parameter
+#[derive(Debug)]
+pub struct Value {
+    pub handle: ValueHandle,
+}
+
+impl From<ValueHandle> for Value {
+    fn from(handle: ValueHandle) -> Self {
+        Self { handle }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        unsafe {
+            let raw = ffi::duckdb_create_varchar_length(value.as_ptr().cast(), value.len() as u64);
+            ValueHandle::from_raw(raw).into()
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        unsafe { ValueHandle::from_raw(ffi::duckdb_create_int64(value)).into() }
+    }
+}
+
+impl Value {
+    /// Value cast to `VARCHAR`
+    pub fn to_varchar(&self) -> String {
+        unsafe {
+            let ptr = ffi::duckdb_get_varchar(**self);
+            let s = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            ffi::duckdb_free(ptr.cast());
+            s
+        }
+    }
+    /// Value cast to `BIGINT`, or `0` if the cast fails
+    pub fn to_i64(&self) -> i64 {
+        unsafe { ffi::duckdb_get_int64(**self) }
+    }
+}
+
+impl Deref for Value {
+    type Target = ffi::duckdb_value;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}